@@ -1,6 +1,24 @@
 use game_context::*;
 use std::time::Duration;
 
+/// Tag for the player platform
+const PLATFORM: Tag = Tag(1);
+
+/// Tag for the breakable blocks
+const BLOCK: Tag = Tag(2);
+
+/// Tag for hit blocks that are fading out
+const DEBRIS: Tag = Tag(3);
+
+/// Tag for the ball
+const BALL: Tag = Tag(4);
+
+/// Tag for the walls around the screen
+const WALL: Tag = Tag(5);
+
+/// Radius of the ball
+const BALL_RADIUS: f32 = 0.2;
+
 // Reset the game state
 fn reset_state(state: &mut Option<State>, macroquad: &Macroquad) {
     let mut world = World::default();
@@ -9,6 +27,12 @@ fn reset_state(state: &mut Option<State>, macroquad: &Macroquad) {
     let platform_height = 1.0;
     let platform_x = 4.0;
 
+    // Load the hand-drawn curve for the platform drop, falling back to a built-in ease
+    let mut drop_curve = KeyframeFile::new("curves/platform_drop.keys");
+    if let Err(err) = drop_curve.reload() {
+        eprintln!("{err}");
+    }
+
     // Initialize the  above the map
     let platform = world.push(Object {
         position: Vec2 {
@@ -20,25 +44,60 @@ fn reset_state(state: &mut Option<State>, macroquad: &Macroquad) {
             width: platform_width,
             height: platform_height,
         },
+        tag: PLATFORM,
+        // Squash the platform towards its bottom center
+        pivot: Vec2::new(platform_width / 2., platform_height),
+        ..Default::default()
     });
 
     // Begin by animating a single platform
-    world.animate(
-        &platform,
-        Animation {
-            target: AnimationState {
-                absolute_position: Some(Vec2 {
-                    x: platform_x,
-                    y: SCREEN_HEIGHT - platform_height,
-                }),
+    world
+        .animate(
+            &platform,
+            Animation {
+                target: AnimationState {
+                    absolute_position: Some(Vec2 {
+                        x: platform_x,
+                        y: SCREEN_HEIGHT - platform_height,
+                    }),
+                    ..Default::default()
+                },
+                ease: drop_curve.ease().cloned().unwrap_or(EaseFunc::SmoothStop2),
+                duration: Duration::from_millis(800).as_secs_f32(),
                 ..Default::default()
             },
-            ease: EaseFunc::SmoothStop2,
-            duration: Duration::from_millis(800).as_secs_f32(),
+        )
+        .expect("Platform was just pushed");
+
+    let mut dropping = 1;
+
+    // Initialize the walls just outside of the screen for the ball to bounce off
+    for (x, y, width, height) in [
+        (-1., -1., 1., SCREEN_HEIGHT + 2.),
+        (SCREEN_WIDTH, -1., 1., SCREEN_HEIGHT + 2.),
+        (-1., -1., SCREEN_WIDTH + 2., 1.),
+        (-1., SCREEN_HEIGHT, SCREEN_WIDTH + 2., 1.),
+    ] {
+        world.push(Object {
+            position: Vec2::new(x, y),
+            shape: Shape::Rectangle { width, height },
+            tag: WALL,
+            ..Default::default()
+        });
+    }
+
+    // Initialize the ball
+    let ball = world.push(Object {
+        position: Vec2::new(12., 7.5),
+        color: RED,
+        shape: Shape::Circle {
+            radius: BALL_RADIUS,
         },
-    );
+        tag: BALL,
+        ..Default::default()
+    });
 
-    // Check for ball collision with blocks
+    // Initialize the blocks
     for j in 0..BLOCKS_HEIGHT {
         for i in 0..BLOCKS_WIDTH {
             let block_padding = 0.2;
@@ -60,6 +119,8 @@ fn reset_state(state: &mut Option<State>, macroquad: &Macroquad) {
                     width: block_w - block_padding,
                     height: block_h - block_padding,
                 },
+                tag: BLOCK,
+                ..Default::default()
             });
 
             /*
@@ -87,34 +148,41 @@ fn reset_state(state: &mut Option<State>, macroquad: &Macroquad) {
             };
 
             // Initially animate the block to fall from the top of the screen
-            world.animate(
-                &block_obj,
-                Animation {
-                    target: AnimationState {
-                        absolute_position: Some(Vec2 {
-                            x: block_x,
-                            y: block_y,
-                        }),
+            world
+                .animate(
+                    &block_obj,
+                    Animation {
+                        target: AnimationState {
+                            absolute_position: Some(Vec2 {
+                                x: block_x,
+                                y: block_y,
+                            }),
+                            ..Default::default()
+                        },
+                        ease,
+                        duration: Duration::from_millis(millis).as_secs_f32(),
+                        delay: (macroquad.gen_range)(0.0, 0.5),
                         ..Default::default()
                     },
-                    ease,
-                    duration: Duration::from_millis(millis).as_secs_f32(),
-                },
-            );
+                )
+                .expect("Block was just pushed");
+
+            dropping += 1;
         }
     }
 
     // Set the initial state
     *state = Some(State {
         reset_initialized: false,
-        blocks: [[true; BLOCKS_WIDTH]; BLOCKS_HEIGHT],
-        ball: Vec2::new(12., 7.),
+        dropping,
+        ball,
         ball_velocity: Vec2::new(6.0, -6.5),
         platform,
         stick: false,
         platform_width: 5.,
-        platform_height: 0.2,
         world,
+        drop_curve,
+        ease_visualizer: EaseVisualizer::default(),
     });
 }
 
@@ -126,8 +194,6 @@ pub extern "C" fn game_update_and_render(
 ) {
     let Macroquad {
         clear_background,
-        draw_circle,
-        is_key_down,
         camera_font_scale,
         draw_text_ex,
         ..
@@ -141,14 +207,15 @@ pub extern "C" fn game_update_and_render(
     // De-structure the game state itself
     let Some(State {
         reset_initialized,
-        blocks,
+        dropping,
         ball,
         ball_velocity,
         platform,
         platform_width,
-        platform_height,
         stick,
         world,
+        drop_curve,
+        ease_visualizer,
     }) = state
     else {
         unreachable!()
@@ -160,14 +227,44 @@ pub extern "C" fn game_update_and_render(
         return;
     }
 
+    // Reset the world to replay the drop whenever the curve file is edited. The
+    // visualizer already shows the new curve, so keep it open while editing.
+    match drop_curve.reload() {
+        Ok(true) if !game.show_ease_visualizer => {
+            *state = None;
+            return;
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("{err}"),
+    }
+
+    // Show the ease visualizer instead of the game, pausing the game underneath
+    if game.show_ease_visualizer {
+        clear_background(BLACK);
+
+        ease_visualizer.update(game.buttons, game.frame_time);
+
+        let mut eases = ease_visualizer.eases();
+        eases.extend(drop_curve.ease().cloned());
+        ease_visualizer.draw(macroquad, &eases);
+
+        return;
+    }
+
     //
     if !*reset_initialized {
         clear_background(BLACK);
 
         world.update(game.frame_time);
 
-        // The world is finished initializing
-        *reset_initialized = !world.animating.iter().any(|x| *x);
+        // The world is finished initializing once every object dropped in
+        let dropped = world
+            .finished_animations()
+            .iter()
+            .filter(|(_, track)| *track == Track::Position)
+            .count();
+        *dropping = dropping.saturating_sub(dropped);
+        *reset_initialized = *dropping == 0;
 
         world.draw(macroquad);
 
@@ -178,27 +275,36 @@ pub extern "C" fn game_update_and_render(
 
     let delta = game.frame_time;
 
-    let platform_pos = world.position(&platform);
+    // The platform is never removed, so a dead platform means the state is stale
+    let Some(platform_pos) = world.position(platform) else {
+        *state = None;
+        return;
+    };
+
+    let mut platform_x = platform_pos.x;
 
     // Right - Move paddle right
     if game.buttons.contains(&KeyCode::Right)
         && platform_pos.x < SCREEN_WIDTH - *platform_width / 2.
     {
-        world.position_mut(&platform).x += 6.0 * delta;
+        platform_x += 6.0 * delta;
     }
 
     // Left - Move paddle right
     if game.buttons.contains(&KeyCode::Left) && platform_pos.x > *platform_width / 2. {
-        let new_x = (platform_pos.x - 6.0 * delta).max(0.0);
-        world.position_mut(&platform).x = new_x;
+        platform_x = (platform_pos.x - 6.0 * delta).max(0.0);
     }
 
-    let platform_x = world.position(&platform).x;
+    if let Some(position) = world.position_mut(platform) {
+        position.x = platform_x;
+    }
 
     // Update the ball or wait for user input to start
-    if *stick == false {
-        ball.x += ball_velocity.x * delta;
-        ball.y += ball_velocity.y * delta;
+    let hits = if *stick == false {
+        // Sweep the ball along its path so it can't skip through anything on a long frame
+        world.move_and_bounce(ball, ball_velocity, delta, |object| {
+            [WALL, PLATFORM, BLOCK].contains(&object.tag)
+        })
     } else {
         let (font_size, font_scale, font_aspect) = camera_font_scale(1.);
         let text_params = TextParams {
@@ -214,81 +320,113 @@ pub extern "C" fn game_update_and_render(
             text_params,
         );
 
-        ball.x = platform_x;
-        ball.y = SCREEN_HEIGHT - 0.5;
+        // Rest the ball on top of the platform
+        if let Some(bounds) = world.bounds(platform) {
+            if let Some(position) = world.position_mut(ball) {
+                *position = Vec2::new(bounds.center().x, bounds.top() - BALL_RADIUS);
+            }
+        }
 
         *stick = !game.buttons.contains(&KeyCode::Space);
-    }
 
-    // Bounce the ball off the left/right walls
-    if ball.x <= 0. || ball.x > SCREEN_WIDTH {
-        // dbg!(&ball_velocity);
+        Vec::new()
+    };
 
-        // Increase/decrease speed of ball on collision
-        ball_velocity.x *= 1.0;
+    let mut hit_platform = false;
+    let mut hit_blocks = Vec::new();
 
-        if ball_velocity.x >= 20.0 {
-            ball_velocity.x = 5.0;
+    for (other, _) in hits {
+        match world.tag(&other) {
+            Some(PLATFORM) => hit_platform = true,
+            Some(BLOCK) if !hit_blocks.contains(&other) => hit_blocks.push(other),
+            _ => {}
         }
+    }
 
-        if ball_velocity.x <= -20.0 {
-            ball_velocity.x = -5.0;
-        }
+    // Bump the platform down and back up while it is still being steered. A single
+    // yoyo animation returns the whole offset, so a bump can't be cut short halfway.
+    if hit_platform && !world.is_track_animating(platform, Track::Position) {
+        let bump = Animation {
+            target: AnimationState {
+                relative_position: Some(Vec2::new(0., 0.3)),
+                additive: true,
+                ..Default::default()
+            },
+            ease: EaseFunc::SmoothStop2,
+            duration: Duration::from_millis(100).as_secs_f32(),
+            repeat: Repeat::Times(2),
+            yoyo: true,
+            ..Default::default()
+        };
 
-        ball_velocity.x *= -1.;
+        world.animate(platform, bump).expect("Platform is alive");
     }
 
-    // Platform collision
-    if ball.y <= 0.
-        || (ball.y > SCREEN_HEIGHT - *platform_height - 0.15 / 2.
-            && ball.x >= platform_x - *platform_width / 2.
-            && ball.x <= platform_x + *platform_width / 2.)
-    {
-        ball_velocity.y *= -1.;
-    }
+    // Squash the platform and let it spring back into shape
+    if hit_platform {
+        if let Some(scale) = world.scale_mut(platform) {
+            *scale = Vec2::new(1.1, 0.6);
+        }
 
-    // Bottom screen kill condition
-    if ball.y >= SCREEN_HEIGHT {
-        // ball.y = 10.;
-        // *stick = true;
-        ball_velocity.y = -ball_velocity.y.abs();
+        world
+            .animate(
+                platform,
+                Animation {
+                    target: AnimationState {
+                        scale: Some(Vec2::ONE),
+                        ..Default::default()
+                    },
+                    spring: Some(Spring {
+                        stiffness: 300.,
+                        damping: 10.,
+                        mass: 1.,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .expect("Platform is alive");
     }
 
-    /*
-    // Check for ball collision with blocks
-    for j in 0..BLOCKS_HEIGHT {
-        for i in 0..BLOCKS_WIDTH {
-            if blocks[j][i] {
-                let block_w = SCREEN_WIDTH / BLOCKS_WIDTH as f32;
-                let block_h = 7.0 / BLOCKS_HEIGHT as f32;
-
-                let block_padding = 0.1;
-
-                let block_x = i as f32 * block_w + block_padding / 2.0;
-                let block_y = j as f32 * block_h + block_padding / 2.0;
-
-                draw_rectangle(
-                    block_x,
-                    block_y,
-                    block_w - block_padding,
-                    block_h - block_padding,
-                    DARKBLUE,
-                );
-                if ball.x >= block_x
-                    && ball.x < block_x + block_w
-                    && ball.y >= block_y
-                    && ball.y < block_y + block_h
-                {
-                    ball_velocity.y *= -1.;
-                    blocks[j][i] = false;
-                }
-            }
+    // Flash the hit blocks white and fade them out
+    for block in &hit_blocks {
+        if let Some(tag) = world.tag_mut(block) {
+            *tag = DEBRIS;
+        }
+
+        if let Some(color) = world.color_mut(block) {
+            *color = WHITE;
         }
+
+        world
+            .animate(
+                block,
+                Animation {
+                    target: AnimationState {
+                        color: Some(RED),
+                        color_mode: ColorMode::Oklab,
+                        alpha: Some(0.),
+                        ..Default::default()
+                    },
+                    ease: EaseFunc::SmoothStop2,
+                    duration: Duration::from_millis(300).as_secs_f32(),
+                    ..Default::default()
+                },
+            )
+            .expect("Block was just hit");
+    }
+
+    // Remove the debris that finished fading out
+    let faded: Vec<ObjectIndex> = world
+        .iter_tag(DEBRIS)
+        .filter(|debris| !debris.animating)
+        .map(|debris| debris.index)
+        .collect();
+
+    for debris in &faded {
+        let _ = world.remove(debris);
     }
-    */
 
-    // Draw the ball
-    draw_circle(ball.x, ball.y, 0.2, RED);
+    world.update(delta);
 
     // Draw the world
     world.draw(macroquad);
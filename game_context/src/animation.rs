@@ -1,16 +1,315 @@
 //! Provide an abstraction over animating objects
 
+use core::ops::{Add, Mul, Sub};
+
 use crate::*;
 
-#[derive(Default)]
+/// An independently animated property of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Track {
+    Position,
+    Rotation,
+    Scale,
+    Color,
+    Alpha,
+}
+
+/// The color space used to interpolate between two colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Interpolate each RGB channel independently
+    #[default]
+    Rgb,
+
+    /// Interpolate hue, saturation and value, taking the shortest path around the hue
+    Hsv,
+
+    /// Interpolate in the perceptually uniform OKLab space
+    Oklab,
+}
+
+impl ColorMode {
+    /// Interpolate from `start` to `end` by `t` in this color space. Alpha is always
+    /// interpolated linearly.
+    pub fn lerp(self, start: Color, end: Color, t: f32) -> Color {
+        let [r, g, b] = match self {
+            ColorMode::Rgb => lerp3([start.r, start.g, start.b], [end.r, end.g, end.b], t),
+            ColorMode::Hsv => {
+                let [h0, s0, v0] = rgb_to_hsv(start);
+                let [mut h1, s1, v1] = rgb_to_hsv(end);
+
+                // Take the shortest way around the hue circle
+                if h1 - h0 > 0.5 {
+                    h1 -= 1.;
+                } else if h0 - h1 > 0.5 {
+                    h1 += 1.;
+                }
+
+                let [h, s, v] = lerp3([h0, s0, v0], [h1, s1, v1], t);
+                hsv_to_rgb([h.rem_euclid(1.), s, v])
+            }
+            ColorMode::Oklab => oklab_to_rgb(lerp3(rgb_to_oklab(start), rgb_to_oklab(end), t)),
+        };
+
+        let a = start.a + (end.a - start.a) * t;
+
+        // Overshooting eases could push the channels out of range
+        Color::new(
+            r.clamp(0., 1.),
+            g.clamp(0., 1.),
+            b.clamp(0., 1.),
+            a.clamp(0., 1.),
+        )
+    }
+}
+
+/// Linearly interpolate each of the three components
+fn lerp3(start: [f32; 3], end: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| start[i] + (end[i] - start[i]) * t)
+}
+
+/// Convert a color to hue, saturation and value, all in `0..=1`
+fn rgb_to_hsv(color: Color) -> [f32; 3] {
+    let Color { r, g, b, .. } = color;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0. {
+        0.
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.)
+    } else if max == g {
+        (b - r) / delta + 2.
+    } else {
+        (r - g) / delta + 4.
+    };
+
+    let saturation = if max == 0. { 0. } else { delta / max };
+
+    [hue / 6., saturation, max]
+}
+
+/// Convert hue, saturation and value, all in `0..=1`, to RGB
+fn hsv_to_rgb([h, s, v]: [f32; 3]) -> [f32; 3] {
+    let chroma = v * s;
+    let h = h * 6.;
+    let x = chroma * (1. - (h.rem_euclid(2.) - 1.).abs());
+    let m = v - chroma;
+
+    let [r, g, b] = match h as u32 {
+        0 => [chroma, x, 0.],
+        1 => [x, chroma, 0.],
+        2 => [0., chroma, x],
+        3 => [0., x, chroma],
+        4 => [x, 0., chroma],
+        _ => [chroma, 0., x],
+    };
+
+    [r + m, g + m, b + m]
+}
+
+/// Convert a gamma encoded sRGB channel to linear
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear channel to gamma encoded sRGB
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.max(0.).powf(1. / 2.4) - 0.055
+    }
+}
+
+/// Convert a color to OKLab
+fn rgb_to_oklab(color: Color) -> [f32; 3] {
+    let [r, g, b] = [color.r, color.g, color.b].map(srgb_to_linear);
+
+    let l = 0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b;
+    let m = 0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b;
+    let s = 0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b;
+
+    let [l, m, s] = [l, m, s].map(f32::cbrt);
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Convert an OKLab color to RGB
+fn oklab_to_rgb([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+    let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+    let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+
+    let [l, m, s] = [l_, m_, s_].map(|c| c * c * c);
+
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960771 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ]
+    .map(linear_to_srgb)
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct AnimationState {
     /// The absolute target coordinates of the animation
     pub absolute_position: Option<Vec2>,
 
-    /// The relative target coordinates of the animation
+    /// The target coordinates relative to the position when the animation starts. Added
+    /// to the absolute target if both are given.
     pub relative_position: Option<Vec2>,
+
+    /// The target rotation (in radians) of the animation
+    pub rotation: Option<f32>,
+
+    /// The target scale of the animation
+    pub scale: Option<Vec2>,
+
+    /// The target color of the animation. Its alpha is ignored in favor of `alpha`.
+    pub color: Option<Color>,
+
+    /// The ease function for the color and alpha, using the animation ease if not set
+    pub color_ease: Option<EaseFunc>,
+
+    /// The color space to interpolate the color in
+    pub color_mode: ColorMode,
+
+    /// The target alpha of the animation
+    pub alpha: Option<f32>,
+
+    /// Follow the position of another object, offset by `relative_position`. The target
+    /// is recomputed every frame from where the object currently is.
+    pub follow: Option<ObjectIndex>,
+
+    /// Add the relative position, rotation, scale and alpha on top of whatever else
+    /// moves the object during the animation instead of overwriting it. Additive
+    /// animations overlapping on the same track add up. The color is still animated
+    /// towards its target.
+    pub additive: bool,
+}
+
+impl AnimationState {
+    /// The tracks this state has a target for
+    pub fn tracks(&self) -> impl Iterator<Item = Track> {
+        let position = self.absolute_position.is_some()
+            || self.relative_position.is_some()
+            || self.follow.is_some();
+
+        [
+            (position, Track::Position),
+            (self.rotation.is_some(), Track::Rotation),
+            (self.scale.is_some(), Track::Scale),
+            (self.color.is_some(), Track::Color),
+            (self.alpha.is_some(), Track::Alpha),
+        ]
+        .into_iter()
+        .filter_map(|(targeted, track)| targeted.then_some(track))
+    }
+}
+
+/// How many times an animation plays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Repeat {
+    /// Play the animation a single time
+    #[default]
+    Once,
+
+    /// Play the animation this many times in total. Zero plays it once like `Once`.
+    Times(u32),
+
+    /// Play the animation until it is replaced or cancelled
+    Forever,
+}
+
+/// Distance and speed below which a spring or smoothed value is considered settled
+const REST: f32 = 0.001;
+
+/// A damped spring pulling a value towards its target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    /// How strongly the spring pulls towards the target
+    pub stiffness: f32,
+
+    /// How strongly the velocity is slowed down
+    pub damping: f32,
+
+    /// The mass of the value on the spring
+    pub mass: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 170.,
+            damping: 26.,
+            mass: 1.,
+        }
+    }
+}
+
+impl Spring {
+    /// Longest time (in seconds) integrated in a single step to keep stiff springs stable
+    const MAX_STEP: f32 = 1. / 240.;
+
+    /// Returns `true` if the spring can be simulated
+    pub fn is_valid(&self) -> bool {
+        self.stiffness > 0. && self.damping >= 0. && self.mass > 0.
+    }
+
+    /// Move `value` with `velocity` towards `target` over `frame_time`. Returns `true`
+    /// once the spring has settled on the target.
+    fn step<T: Animatable>(
+        &self,
+        value: &mut T,
+        velocity: &mut T,
+        target: T,
+        frame_time: f32,
+    ) -> bool {
+        // Split the frame into equal steps so the result only depends on the frame time
+        let steps = (frame_time / Self::MAX_STEP).ceil().max(1.);
+        let dt = frame_time / steps;
+
+        for _ in 0..steps as u32 {
+            let force = (target - *value) * self.stiffness - *velocity * self.damping;
+            *velocity = *velocity + force * (dt / self.mass);
+            *value = *value + *velocity * dt;
+        }
+
+        (target - *value).magnitude() < REST && velocity.magnitude() < REST
+    }
+}
+
+/// A value that can be interpolated, added to and driven by a [`Spring`]
+pub trait Animatable:
+    Copy + Default + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+    /// The size of this value, used to decide when a spring has settled
+    fn magnitude(self) -> f32;
+}
+
+impl Animatable for f32 {
+    fn magnitude(self) -> f32 {
+        self.abs()
+    }
 }
 
+impl Animatable for Vec2 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Animation {
     /// The target state of this object animation
     pub target: AnimationState,
@@ -20,4 +319,538 @@ pub struct Animation {
 
     /// How long this animation should last (in seconds)
     pub duration: f32,
+
+    /// How long to wait before starting this animation (in seconds)
+    pub delay: f32,
+
+    /// How many times to play this animation
+    pub repeat: Repeat,
+
+    /// Play every other iteration backwards, back to the start
+    pub yoyo: bool,
+
+    /// How long to wait between iterations (in seconds)
+    pub repeat_delay: f32,
+
+    /// Drive the position, rotation and scale with a spring instead of the ease. The
+    /// duration and repeats only apply to the color and alpha of a spring animation.
+    pub spring: Option<Spring>,
+
+    /// Instead of the ease, move the position, rotation and scale towards the target
+    /// by exponential decay with this rate (per second). The remaining distance shrinks
+    /// by a factor of `exp(-rate * t)` after `t` seconds, like `x += (target - x) * .1`
+    /// every frame but independent of the frame rate. Ignored if `spring` is set.
+    pub smoothing: Option<f32>,
+}
+
+/// The progress made by a [`Tween`] during one update
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TweenStep {
+    /// The eased progress of the tween
+    pub(crate) progress: f32,
+
+    /// The eased progress made since the last update
+    pub(crate) step: f32,
+
+    /// Did the tween start during this update
+    pub(crate) started: bool,
+
+    /// Did the tween finish during this update
+    pub(crate) finished: bool,
+}
+
+/// The timing of a single animation track of an object
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TweenTiming {
+    /// Is the track running or waiting for its delay
+    pub(crate) active: bool,
+
+    /// Has the delay passed and the start value been captured
+    started: bool,
+
+    /// Remaining time before the track starts (in seconds)
+    delay: f32,
+
+    /// The total time of the track (in seconds)
+    duration: f32,
+
+    /// The elapsed time of the track (in seconds)
+    elapsed: f32,
+
+    /// The ease function of the track
+    ease: EaseFunc,
+
+    /// The eased progress already applied
+    progress: f32,
+
+    /// Is the target relative to the value when the track starts
+    relative: bool,
+
+    /// Is the target added on top of the value instead of overwriting it
+    additive: bool,
+
+    /// How many times the track plays
+    repeat: Repeat,
+
+    /// Number of finished iterations
+    iteration: u32,
+
+    /// Does every other iteration play backwards
+    yoyo: bool,
+
+    /// Time to wait between iterations (in seconds)
+    repeat_delay: f32,
+
+    /// The spring driving the track instead of the ease
+    spring: Option<Spring>,
+
+    /// The exponential smoothing rate driving the track instead of the ease
+    smoothing: Option<f32>,
+
+    /// Does the target move while the track runs, keeping springs and smoothing from
+    /// finishing once they reach it
+    moving_target: bool,
+}
+
+impl TweenTiming {
+    /// Timing of a track started by `animation`, eased by `ease`
+    pub(crate) fn new(animation: &Animation, ease: &EaseFunc) -> Self {
+        Self {
+            active: true,
+            delay: animation.delay,
+            duration: animation.duration,
+            ease: ease.clone(),
+            additive: animation.target.additive,
+            repeat: animation.repeat,
+            yoyo: animation.yoyo,
+            repeat_delay: animation.repeat_delay,
+            ..Default::default()
+        }
+    }
+
+    /// Drive the track with the spring or smoothing of the animation, if it has one
+    pub(crate) fn with_motion(mut self, animation: &Animation) -> Self {
+        self.spring = animation.spring;
+        self.smoothing = animation.smoothing;
+        self
+    }
+
+    /// The target is moved every frame while the track runs
+    pub(crate) fn moving_target(mut self) -> Self {
+        self.moving_target = true;
+        self
+    }
+
+    /// Resolve the target against the value when the track starts
+    pub(crate) fn relative(mut self) -> Self {
+        self.relative = true;
+        self
+    }
+
+    /// Returns `true` if another iteration plays after the current one
+    fn repeats(&self) -> bool {
+        match self.repeat {
+            Repeat::Once => false,
+            Repeat::Times(times) => self.iteration + 1 < times,
+            Repeat::Forever => true,
+        }
+    }
+
+    /// Linear progress of the active track, `0.` while it waits for its delay. Spring
+    /// and smoothing tracks have no fixed duration and always report `0.`.
+    pub(crate) fn linear_progress(&self) -> Option<f32> {
+        if !self.active {
+            return None;
+        }
+
+        if self.spring.is_some() || self.smoothing.is_some() {
+            return Some(0.);
+        }
+
+        Some(self.linear())
+    }
+
+    /// Linear progress of the current iteration, a track without a duration is done
+    /// right away
+    fn linear(&self) -> f32 {
+        if self.duration <= 0. {
+            return 1.;
+        }
+
+        (self.elapsed / self.duration).clamp(0., 1.)
+    }
+
+    /// Count down the delay of the track. Returns the time left in this frame for the
+    /// track itself and whether the track started during this frame, or `None` if the
+    /// track is not running.
+    fn wait(&mut self, frame_time: f32) -> Option<(f32, bool)> {
+        if !self.active {
+            return None;
+        }
+
+        if self.started {
+            return Some((frame_time, false));
+        }
+
+        if self.delay > frame_time {
+            self.delay -= frame_time;
+            return None;
+        }
+
+        // The rest of the frame is spent on the track itself
+        let frame_time = frame_time - self.delay;
+        self.delay = 0.;
+        self.started = true;
+
+        Some((frame_time, true))
+    }
+
+    /// Advance the timing by `frame_time`, returning `None` if nothing moved
+    fn advance(&mut self, frame_time: f32) -> Option<TweenStep> {
+        let (frame_time, started) = self.wait(frame_time)?;
+
+        self.elapsed += frame_time;
+
+        // Get the current progress of this track, landing exactly on the target. A
+        // negative elapsed time is the delay before the next iteration.
+        let mut linear = self.linear();
+
+        // Every other iteration plays backwards for yoyo tracks
+        if self.yoyo && self.iteration % 2 == 1 {
+            linear = 1. - linear;
+        }
+
+        let progress = self.ease.calculate(linear);
+        let step = progress - self.progress;
+        self.progress = progress;
+
+        let mut finished = false;
+
+        if self.elapsed >= self.duration {
+            if self.repeats() {
+                // Carry the time past the end of this iteration into the next one so
+                // that the iterations only depend on the frame time
+                self.iteration += 1;
+                self.elapsed -= self.duration + self.repeat_delay;
+            } else {
+                self.active = false;
+                finished = true;
+            }
+        }
+
+        Some(TweenStep {
+            progress,
+            step,
+            started,
+            finished,
+        })
+    }
+}
+
+/// A single animation track of an object, moving a value from its start to its target
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tween<T> {
+    /// The timing of this track
+    pub(crate) timing: TweenTiming,
+
+    /// The value when the track started
+    pub(crate) start: T,
+
+    /// The value to finish on, or the offset for relative and additive tracks
+    pub(crate) target: T,
+
+    /// The speed of the value (per second), kept when a spring is retargeted
+    velocity: T,
+}
+
+impl<T: Copy + Default> Tween<T> {
+    /// Create a track towards `target` with the given `timing`
+    pub(crate) fn new(target: T, timing: TweenTiming) -> Self {
+        Self {
+            timing,
+            start: T::default(),
+            target,
+            velocity: T::default(),
+        }
+    }
+
+    /// Advance the track by `frame_time`, capturing `current` as the start value when
+    /// the track starts
+    pub(crate) fn advance(&mut self, current: T, frame_time: f32) -> Option<TweenStep> {
+        let step = self.timing.advance(frame_time)?;
+
+        if step.started {
+            self.start = current;
+        }
+
+        Some(step)
+    }
+}
+
+impl<T: Animatable> Tween<T> {
+    /// Replace this track with one towards `target`, keeping the velocity of the value
+    /// if this track is still running
+    ///
+    /// An additive track replacing a running additive track that plays once takes over
+    /// the offset not applied yet, so that overlapping additive animations add up.
+    pub(crate) fn retarget(&mut self, mut target: T, timing: TweenTiming) {
+        let velocity = if self.timing.active {
+            self.velocity
+        } else {
+            T::default()
+        };
+
+        if self.timing.active
+            && self.timing.additive
+            && self.timing.repeat == Repeat::Once
+            && timing.additive
+        {
+            target = target + self.target * (1. - self.timing.progress);
+        }
+
+        *self = Self {
+            velocity,
+            ..Self::new(target, timing)
+        };
+    }
+
+    /// Advance the track by `frame_time` and write the new value to `value`. Returns
+    /// `true` if the track finished during this update.
+    pub(crate) fn apply(&mut self, value: &mut T, frame_time: f32) -> bool {
+        if let Some(spring) = self.timing.spring {
+            return self.apply_spring(spring, value, frame_time);
+        }
+
+        let before = *value;
+        let finished = match self.timing.smoothing {
+            Some(rate) => self.apply_smoothing(rate, value, frame_time),
+            None => self.apply_ease(value, frame_time),
+        };
+
+        // Keep track of the velocity so that a spring can take over smoothly
+        if frame_time > 0. {
+            self.velocity = (*value - before) * (1. / frame_time);
+        }
+
+        finished
+    }
+
+    /// Resolve a relative target against the value at the start of the track
+    fn resolve(&mut self) {
+        if self.timing.relative && !self.timing.additive {
+            self.target = self.start + self.target;
+        }
+    }
+
+    /// Move `value` along the spring of this track
+    fn apply_spring(&mut self, spring: Spring, value: &mut T, frame_time: f32) -> bool {
+        let Some((frame_time, started)) = self.timing.wait(frame_time) else {
+            return false;
+        };
+
+        if started {
+            self.start = *value;
+            self.resolve();
+        }
+
+        let settled = spring.step(value, &mut self.velocity, self.target, frame_time)
+            && !self.timing.moving_target;
+
+        // Snap onto the target once the spring has settled
+        if settled {
+            *value = self.target;
+            self.velocity = T::default();
+            self.timing.active = false;
+        }
+
+        settled
+    }
+
+    /// Move `value` towards the target of this track by exponential smoothing
+    fn apply_smoothing(&mut self, rate: f32, value: &mut T, frame_time: f32) -> bool {
+        let Some((frame_time, started)) = self.timing.wait(frame_time) else {
+            return false;
+        };
+
+        if started {
+            self.start = *value;
+            self.resolve();
+        }
+
+        *value = *value + (self.target - *value) * (1. - (-rate * frame_time).exp());
+
+        // Snap onto the target once close enough
+        let settled = (self.target - *value).magnitude() < REST && !self.timing.moving_target;
+        if settled {
+            *value = self.target;
+            self.timing.active = false;
+        }
+
+        settled
+    }
+
+    /// Move `value` along the ease of this track
+    fn apply_ease(&mut self, value: &mut T, frame_time: f32) -> bool {
+        let Some(step) = self.advance(*value, frame_time) else {
+            return false;
+        };
+
+        if step.started {
+            self.resolve();
+        }
+
+        if self.timing.additive {
+            // Only add the progress made since the last update so that changes made
+            // by the game in between are kept
+            *value = *value + self.target * step.step;
+        } else {
+            *value = self.start + (self.target - self.start) * step.progress;
+        }
+
+        step.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_duration_finishes_on_first_update() {
+        let animation = Animation {
+            duration: 0.,
+            ..Default::default()
+        };
+        let mut tween = Tween::new(2_f32, TweenTiming::new(&animation, &animation.ease));
+        let mut value = 0.;
+
+        assert!(tween.apply(&mut value, 1. / 60.));
+        assert_eq!(value, 2.);
+        assert!(!tween.timing.active);
+    }
+
+    /// A linear tween from 0 to 1 over a second with the given repeats
+    fn repeating(repeat: Repeat, yoyo: bool) -> Tween<f32> {
+        let animation = Animation {
+            ease: EaseFunc::Linear,
+            duration: 1.,
+            repeat,
+            yoyo,
+            ..Default::default()
+        };
+
+        Tween::new(1., TweenTiming::new(&animation, &animation.ease))
+    }
+
+    #[test]
+    fn repeat_carries_time_into_next_iteration() {
+        let mut tween = repeating(Repeat::Times(2), false);
+        let mut value = 0.;
+
+        assert!(!tween.apply(&mut value, 0.75));
+        assert_eq!(value, 0.75);
+
+        // The frame crossing the end lands on it and the quarter second past the end
+        // is played in the second iteration
+        assert!(!tween.apply(&mut value, 0.5));
+        assert_eq!(value, 1.);
+        assert!(!tween.apply(&mut value, 0.25));
+        assert_eq!(value, 0.5);
+
+        assert!(tween.apply(&mut value, 0.5));
+        assert_eq!(value, 1.);
+    }
+
+    #[test]
+    fn yoyo_plays_back_with_carried_time() {
+        let mut tween = repeating(Repeat::Times(2), true);
+        let mut value = 0.;
+
+        assert!(!tween.apply(&mut value, 0.75));
+        assert!(!tween.apply(&mut value, 0.5));
+        assert_eq!(value, 1.);
+        assert!(!tween.apply(&mut value, 0.25));
+        assert_eq!(value, 0.5);
+
+        assert!(tween.apply(&mut value, 0.5));
+        assert_eq!(value, 0.);
+    }
+
+    /// A tween on the default spring towards `target`
+    fn springy(target: f32) -> Tween<f32> {
+        let animation = Animation {
+            spring: Some(Spring::default()),
+            ..Default::default()
+        };
+
+        let timing = TweenTiming::new(&animation, &animation.ease).with_motion(&animation);
+        Tween::new(target, timing)
+    }
+
+    #[test]
+    fn spring_settles_onto_target() {
+        let mut tween = springy(1.);
+        let mut value = 0.;
+
+        let mut frames = 0;
+        while !tween.apply(&mut value, 1. / 60.) {
+            frames += 1;
+            assert!(frames < 600, "Spring never settled");
+        }
+
+        // Snapped exactly onto the target and at rest
+        assert_eq!(value, 1.);
+        assert_eq!(tween.velocity, 0.);
+        assert!(!tween.timing.active);
+    }
+
+    #[test]
+    fn spring_is_independent_of_frame_rate() {
+        let mut slow = springy(1.);
+        let mut fast = springy(1.);
+        let (mut slow_value, mut fast_value) = (0., 0.);
+
+        for _ in 0..15 {
+            slow.apply(&mut slow_value, 1. / 30.);
+        }
+        for _ in 0..60 {
+            fast.apply(&mut fast_value, 1. / 120.);
+        }
+
+        assert!((slow_value - fast_value).abs() < 1e-4);
+        assert!((slow.velocity - fast.velocity).abs() < 1e-3);
+    }
+
+    #[test]
+    fn retargeted_spring_keeps_velocity() {
+        let mut tween = springy(1.);
+        let mut value = 0.;
+
+        for _ in 0..6 {
+            tween.apply(&mut value, 1. / 60.);
+        }
+        let velocity = tween.velocity;
+        assert!(velocity > 0.);
+
+        // Pulled back the other way, the value keeps moving forward for a moment
+        let timing = tween.timing.clone();
+        tween.retarget(-1., timing);
+        assert_eq!(tween.velocity, velocity);
+
+        let before = value;
+        tween.apply(&mut value, 1. / 240.);
+        assert!(value > before);
+        assert!(tween.velocity < velocity);
+    }
+
+    #[test]
+    fn zero_repeats_play_once() {
+        let mut tween = repeating(Repeat::Times(0), false);
+        let mut value = 0.;
+
+        assert!(!tween.apply(&mut value, 0.5));
+        assert!(tween.apply(&mut value, 0.5));
+        assert_eq!(value, 1.);
+        assert!(!tween.timing.active);
+    }
 }
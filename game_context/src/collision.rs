@@ -0,0 +1,739 @@
+//! Collision tests between the shapes of the world objects
+
+use crate::*;
+
+/// The collision shape of an object in world coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collider {
+    /// A circle around `center`
+    Circle { center: Vec2, radius: f32 },
+
+    /// An axis aligned rectangle
+    Rect(Rect),
+}
+
+/// Where two colliders overlap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// Unit normal pointing from the other collider towards this one. Moving this
+    /// collider by `normal * depth` separates the two.
+    pub normal: Vec2,
+
+    /// How far the colliders overlap along the normal
+    pub depth: f32,
+
+    /// Point of contact on the surface of the other collider
+    pub point: Vec2,
+}
+
+/// Where a moving collider first touches another collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// Fraction of the motion in `0..=1` travelled before touching
+    pub time: f32,
+
+    /// Unit normal of the surface that was hit, pointing towards the moving collider
+    pub normal: Vec2,
+
+    /// Point of contact at the time of impact
+    pub point: Vec2,
+}
+
+impl Contact {
+    /// The same contact seen from the other collider, with the point moved onto the
+    /// surface of this collider
+    pub fn flip(self) -> Self {
+        Self {
+            normal: -self.normal,
+            depth: self.depth,
+            point: self.point - self.normal * self.depth,
+        }
+    }
+}
+
+impl Collider {
+    /// Get the contact of this collider with `other`, or `None` if they don't overlap.
+    /// Colliders that only touch don't overlap.
+    pub fn contact(&self, other: &Collider) -> Option<Contact> {
+        match (*self, *other) {
+            (
+                Collider::Circle { center, radius },
+                Collider::Circle {
+                    center: other_center,
+                    radius: other_radius,
+                },
+            ) => circle_circle(center, radius, other_center, other_radius),
+            (Collider::Circle { center, radius }, Collider::Rect(rect)) => {
+                circle_rect(center, radius, rect)
+            }
+            (Collider::Rect(rect), Collider::Circle { center, radius }) => {
+                circle_rect(center, radius, rect).map(Contact::flip)
+            }
+            (Collider::Rect(rect), Collider::Rect(other)) => rect_rect(rect, other),
+        }
+    }
+
+    /// Move this collider by `motion` and get where it first hits `other`, or `None` if
+    /// it misses. Colliders that already overlap hit immediately, unless the motion
+    /// separates them.
+    pub fn sweep(&self, motion: Vec2, other: &Collider) -> Option<Hit> {
+        // Overlapping colliders only hit if moving further into each other
+        if let Some(contact) = self.contact(other) {
+            return (motion.dot(contact.normal) < 0.).then_some(Hit {
+                time: 0.,
+                normal: contact.normal,
+                point: contact.point,
+            });
+        }
+
+        match (*self, *other) {
+            (
+                Collider::Circle { center, radius },
+                Collider::Circle {
+                    center: other_center,
+                    radius: other_radius,
+                },
+            ) => {
+                let time = ray_circle(center, motion, other_center, radius + other_radius)?;
+                let normal = (center + motion * time - other_center).normalize_or_zero();
+
+                Some(Hit {
+                    time,
+                    normal,
+                    point: other_center + normal * other_radius,
+                })
+            }
+            (Collider::Circle { center, radius }, Collider::Rect(rect)) => {
+                sweep_circle_rect(center, radius, motion, rect)
+            }
+            (Collider::Rect(rect), Collider::Circle { center, radius }) => {
+                // Sweep the circle towards the rectangle instead
+                let hit = sweep_circle_rect(center, radius, -motion, rect)?;
+
+                Some(Hit {
+                    time: hit.time,
+                    normal: -hit.normal,
+                    point: hit.point + motion * hit.time,
+                })
+            }
+            (Collider::Rect(rect), Collider::Rect(other)) => {
+                // Sweep the center against the other rectangle grown by the half size
+                let half = rect.size() / 2.;
+                let min = other.point();
+                let max = min + other.size();
+                let (time, normal) = ray_rect(rect.center(), motion, min - half, max + half)?;
+
+                Some(Hit {
+                    time,
+                    normal,
+                    point: (rect.center() + motion * time).clamp(min, max),
+                })
+            }
+        }
+    }
+}
+
+/// Time in `0..=1` when the ray from `origin` along `motion` enters the rectangle from
+/// `min` to `max`, with the normal of the side it enters through
+fn ray_rect(origin: Vec2, motion: Vec2, min: Vec2, max: Vec2) -> Option<(f32, Vec2)> {
+    let mut enter = (f32::NEG_INFINITY, Vec2::ZERO);
+    let mut exit = f32::INFINITY;
+
+    for (axis, normal) in [(0, Vec2::X), (1, Vec2::Y)] {
+        let (origin, motion, min, max) = (origin[axis], motion[axis], min[axis], max[axis]);
+
+        // Moving parallel to the slab never enters it from outside
+        if motion == 0. {
+            if origin < min || origin > max {
+                return None;
+            }
+
+            continue;
+        }
+
+        // Times when the ray crosses the near and far side of the slab
+        let (near, far, normal) = if motion > 0. {
+            ((min - origin) / motion, (max - origin) / motion, -normal)
+        } else {
+            ((max - origin) / motion, (min - origin) / motion, normal)
+        };
+
+        if near > enter.0 {
+            enter = (near, normal);
+        }
+
+        exit = exit.min(far);
+    }
+
+    let (time, normal) = enter;
+    if time > exit || time > 1. || exit < 0. {
+        return None;
+    }
+
+    Some((time.max(0.), normal))
+}
+
+/// Time in `0..=1` when the ray from `origin` along `motion` enters the circle
+fn ray_circle(origin: Vec2, motion: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let offset = origin - center;
+
+    // Solve |offset + motion * t| = radius for the first t
+    let a = motion.length_squared();
+    let b = 2. * offset.dot(motion);
+    let c = offset.length_squared() - radius * radius;
+
+    if a == 0. {
+        return None;
+    }
+
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / (2. * a);
+    (0. ..=1.).contains(&time).then_some(time)
+}
+
+/// Where the circle at `center` moving by `motion` first hits the rectangle `rect`
+fn sweep_circle_rect(center: Vec2, radius: f32, motion: Vec2, rect: Rect) -> Option<Hit> {
+    let min = rect.point();
+    let max = min + rect.size();
+
+    // Sweep the center against the rectangle grown by the radius
+    let (time, normal) = ray_rect(center, motion, min - radius, max + radius)?;
+    let position = center + motion * time;
+
+    // Hitting a side of the rectangle
+    if (min.x..=max.x).contains(&position.x) || (min.y..=max.y).contains(&position.y) {
+        return Some(Hit {
+            time,
+            normal,
+            point: position - normal * radius,
+        });
+    }
+
+    // Entering near a corner, where the grown rectangle is rounded
+    let corner = position.clamp(min, max);
+    let time = ray_circle(center, motion, corner, radius)?;
+
+    Some(Hit {
+        time,
+        normal: (center + motion * time - corner).normalize_or_zero(),
+        point: corner,
+    })
+}
+
+/// Contact of the circle `a` with the circle `b`
+fn circle_circle(a: Vec2, a_radius: f32, b: Vec2, b_radius: f32) -> Option<Contact> {
+    let offset = a - b;
+    let distance = offset.length();
+    let radii = a_radius + b_radius;
+
+    if distance >= radii {
+        return None;
+    }
+
+    // Concentric circles have no direction to separate in, so push `a` up
+    let normal = if distance > 0. {
+        offset / distance
+    } else {
+        Vec2::NEG_Y
+    };
+
+    Some(Contact {
+        normal,
+        depth: radii - distance,
+        point: b + normal * b_radius,
+    })
+}
+
+/// Contact of the circle at `center` with the rectangle `rect`
+fn circle_rect(center: Vec2, radius: f32, rect: Rect) -> Option<Contact> {
+    let min = rect.point();
+    let max = min + rect.size();
+    let closest = center.clamp(min, max);
+    let offset = center - closest;
+    let distance_squared = offset.length_squared();
+
+    if distance_squared >= radius * radius {
+        return None;
+    }
+
+    // The center is outside the rectangle, so the closest point is on its surface
+    if distance_squared > 0. {
+        let distance = distance_squared.sqrt();
+
+        return Some(Contact {
+            normal: offset / distance,
+            depth: radius - distance,
+            point: closest,
+        });
+    }
+
+    // The center is inside the rectangle, so push it out through the nearest side
+    let sides = [
+        (center.x - min.x, Vec2::NEG_X),
+        (max.x - center.x, Vec2::X),
+        (center.y - min.y, Vec2::NEG_Y),
+        (max.y - center.y, Vec2::Y),
+    ];
+
+    let (distance, normal) = sides
+        .into_iter()
+        .reduce(|nearest, side| if side.0 < nearest.0 { side } else { nearest })
+        .unwrap();
+
+    Some(Contact {
+        normal,
+        depth: radius + distance,
+        point: center + normal * distance,
+    })
+}
+
+/// Contact of the rectangle `a` with the rectangle `b`
+fn rect_rect(a: Rect, b: Rect) -> Option<Contact> {
+    let overlap = Vec2::new(
+        a.right().min(b.right()) - a.left().max(b.left()),
+        a.bottom().min(b.bottom()) - a.top().max(b.top()),
+    );
+
+    if overlap.x <= 0. || overlap.y <= 0. {
+        return None;
+    }
+
+    // Separate along the axis with the least overlap, away from the center of `b`
+    let offset = a.center() - b.center();
+    let (normal, depth) = if overlap.x < overlap.y {
+        (
+            Vec2::new(if offset.x < 0. { -1. } else { 1. }, 0.),
+            overlap.x,
+        )
+    } else {
+        (
+            Vec2::new(0., if offset.y < 0. { -1. } else { 1. }),
+            overlap.y,
+        )
+    };
+
+    // Center of the overlapping area, moved onto the surface of `b`
+    let center = Vec2::new(
+        a.left().max(b.left()) + overlap.x / 2.,
+        a.top().max(b.top()) + overlap.y / 2.,
+    );
+    let point = if normal.x != 0. {
+        Vec2::new(if normal.x < 0. { b.left() } else { b.right() }, center.y)
+    } else {
+        Vec2::new(center.x, if normal.y < 0. { b.top() } else { b.bottom() })
+    };
+
+    Some(Contact {
+        normal,
+        depth,
+        point,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tolerance for compared positions
+    const EPSILON: f32 = 1e-5;
+
+    fn circle(x: f32, y: f32, radius: f32) -> Collider {
+        Collider::Circle {
+            center: Vec2::new(x, y),
+            radius,
+        }
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Collider {
+        Collider::Rect(Rect::new(x, y, w, h))
+    }
+
+    fn assert_contact(contact: Option<Contact>, normal: Vec2, depth: f32, point: Vec2) {
+        let contact = contact.expect("Expected a contact");
+
+        assert!(
+            contact.normal.abs_diff_eq(normal, EPSILON),
+            "normal {} != {normal}",
+            contact.normal
+        );
+        assert!(
+            (contact.depth - depth).abs() < EPSILON,
+            "depth {} != {depth}",
+            contact.depth
+        );
+        assert!(
+            contact.point.abs_diff_eq(point, EPSILON),
+            "point {} != {point}",
+            contact.point
+        );
+    }
+
+    #[test]
+    fn circle_circle() {
+        let a = circle(1.5, 0., 1.);
+        let b = circle(0., 0., 1.);
+
+        assert_contact(a.contact(&b), Vec2::X, 0.5, Vec2::new(1., 0.));
+        assert_contact(b.contact(&a), Vec2::NEG_X, 0.5, Vec2::new(0.5, 0.));
+
+        // Touching and separated circles
+        assert_eq!(circle(2., 0., 1.).contact(&b), None);
+        assert_eq!(circle(0., 3., 1.).contact(&b), None);
+
+        // Concentric circles still separate
+        assert_contact(
+            circle(0., 0., 0.5).contact(&b),
+            Vec2::NEG_Y,
+            1.5,
+            Vec2::new(0., -1.),
+        );
+    }
+
+    #[test]
+    fn circle_rect_side() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Ball dropping onto the top
+        assert_contact(
+            circle(1., -0.1, 0.2).contact(&block),
+            Vec2::NEG_Y,
+            0.1,
+            Vec2::new(1., 0.),
+        );
+
+        // Ball hitting the right side
+        assert_contact(
+            circle(4.15, 1., 0.2).contact(&block),
+            Vec2::X,
+            0.05,
+            Vec2::new(4., 1.),
+        );
+
+        assert_eq!(circle(1., -0.2, 0.2).contact(&block), None);
+        assert_eq!(circle(5., 1., 0.2).contact(&block), None);
+    }
+
+    #[test]
+    fn circle_rect_corner() {
+        let block = rect(0., 0., 4., 2.);
+        let offset = Vec2::new(-0.1, -0.1);
+
+        let normal = offset.normalize();
+        assert_contact(
+            circle(offset.x, offset.y, 0.2).contact(&block),
+            normal,
+            0.2 - offset.length(),
+            Vec2::ZERO,
+        );
+
+        // Inside the bounds of the circle, but not of the circle itself
+        assert_eq!(circle(-0.15, -0.15, 0.2).contact(&block), None);
+    }
+
+    #[test]
+    fn circle_rect_inside() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Center closest to the bottom side
+        assert_contact(
+            circle(1., 1.7, 0.2).contact(&block),
+            Vec2::Y,
+            0.5,
+            Vec2::new(1., 2.),
+        );
+    }
+
+    #[test]
+    fn rect_circle() {
+        let block = rect(0., 0., 4., 2.);
+        let ball = circle(1., -0.1, 0.2);
+
+        // The same contact as circle-rect, pointing at the rectangle from the circle
+        assert_contact(block.contact(&ball), Vec2::Y, 0.1, Vec2::new(1., 0.1));
+    }
+
+    #[test]
+    fn flip_moves_point_to_other_surface() {
+        let (a, b) = (circle(1.5, 0., 1.), circle(0., 0., 1.));
+        let (ab, ba) = (a.contact(&b).unwrap(), b.contact(&a).unwrap());
+
+        assert!(ab.flip().point.abs_diff_eq(ba.point, EPSILON));
+        assert!(ab.flip().normal.abs_diff_eq(ba.normal, EPSILON));
+    }
+
+    #[test]
+    fn rect_rect() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Overlapping the left side of the block
+        assert_contact(
+            rect(-1., 0.5, 1.5, 1.).contact(&block),
+            Vec2::NEG_X,
+            0.5,
+            Vec2::new(0., 1.),
+        );
+
+        // Overlapping the bottom of the block
+        assert_contact(
+            rect(1., 1.8, 2., 1.).contact(&block),
+            Vec2::Y,
+            0.2,
+            Vec2::new(2., 2.),
+        );
+
+        assert_eq!(rect(4., 0., 1., 1.).contact(&block), None);
+        assert_eq!(rect(5., 5., 1., 1.).contact(&block), None);
+    }
+
+    #[test]
+    fn world_objects() {
+        let mut world = World::default();
+
+        let ball = world.push(Object {
+            position: Vec2::new(1., -0.1),
+            shape: Shape::Circle { radius: 0.2 },
+            ..Default::default()
+        });
+
+        let block = world.push(Object {
+            position: Vec2::ZERO,
+            shape: Shape::Rectangle {
+                width: 4.,
+                height: 2.,
+            },
+            ..Default::default()
+        });
+
+        let far = world.push(Object {
+            position: Vec2::new(10., 10.),
+            ..Default::default()
+        });
+
+        assert_contact(
+            world.contact(&ball, &block),
+            Vec2::NEG_Y,
+            0.1,
+            Vec2::new(1., 0.),
+        );
+        assert_eq!(world.contact(&ball, &far), None);
+
+        let contacts: Vec<ObjectIndex> = world.contacts(&ball).map(|(other, _)| other).collect();
+        assert_eq!(contacts, [block]);
+
+        // Removed objects don't collide
+        world.remove(&block).unwrap();
+        assert_eq!(world.contact(&ball, &block), None);
+        assert_eq!(world.contacts(&ball).count(), 0);
+    }
+
+    fn assert_hit(hit: Option<Hit>, time: f32, normal: Vec2, point: Vec2) {
+        let hit = hit.expect("Expected a hit");
+
+        assert!(
+            (hit.time - time).abs() < EPSILON,
+            "time {} != {time}",
+            hit.time
+        );
+        assert!(
+            hit.normal.abs_diff_eq(normal, EPSILON),
+            "normal {} != {normal}",
+            hit.normal
+        );
+        assert!(
+            hit.point.abs_diff_eq(point, EPSILON),
+            "point {} != {point}",
+            hit.point
+        );
+    }
+
+    #[test]
+    fn sweep_circle_rect_side() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Ball moving down onto the top, touching halfway through the motion
+        assert_hit(
+            circle(1., -1.2, 0.2).sweep(Vec2::new(0., 2.), &block),
+            0.5,
+            Vec2::NEG_Y,
+            Vec2::new(1., 0.),
+        );
+
+        // Ball moving diagonally into the left side
+        assert_hit(
+            circle(-1.2, 0., 0.2).sweep(Vec2::new(2., 2.), &block),
+            0.5,
+            Vec2::NEG_X,
+            Vec2::new(0., 1.),
+        );
+    }
+
+    #[test]
+    fn sweep_does_not_tunnel() {
+        // Thin paddle far thinner than the distance the ball moves in a frame
+        let paddle = rect(0., 10., 4., 0.1);
+        let ball = circle(2., 5., 0.2);
+        let motion = Vec2::new(0., 100.);
+
+        // The ball is on either side of the paddle at the start and end of the frame
+        assert_eq!(ball.contact(&paddle), None);
+        assert_eq!(circle(2., 105., 0.2).contact(&paddle), None);
+
+        assert_hit(
+            ball.sweep(motion, &paddle),
+            (10. - 0.2 - 5.) / 100.,
+            Vec2::NEG_Y,
+            Vec2::new(2., 10.),
+        );
+    }
+
+    #[test]
+    fn sweep_circle_rect_corner() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Moving diagonally straight at the top left corner
+        let hit = circle(-1., -1., 0.2)
+            .sweep(Vec2::new(2., 2.), &block)
+            .expect("Expected a hit");
+
+        let expected = (2_f32.sqrt() - 0.2) / 2_f32.sqrt() / 2.;
+        assert!((hit.time - expected).abs() < EPSILON, "time {}", hit.time);
+        assert!(hit
+            .normal
+            .abs_diff_eq(Vec2::new(-1., -1.).normalize(), EPSILON));
+        assert!(hit.point.abs_diff_eq(Vec2::ZERO, EPSILON));
+
+        // Passing the corner 0.25 away, within the grown rectangle but outside the
+        // rounding
+        let away = Vec2::new(-1., -1.).normalize() * 0.25;
+        let along = Vec2::new(1., -1.).normalize();
+        let start = away - along;
+        assert_eq!(
+            circle(start.x, start.y, 0.2).sweep(along * 2., &block),
+            None
+        );
+    }
+
+    #[test]
+    fn sweep_misses() {
+        let block = rect(0., 0., 4., 2.);
+
+        // Moving parallel to the top, moving away and stopping short
+        assert_eq!(circle(0., -1., 0.2).sweep(Vec2::new(10., 0.), &block), None);
+        assert_eq!(circle(1., -1., 0.2).sweep(Vec2::new(0., -5.), &block), None);
+        assert_eq!(circle(1., -1., 0.2).sweep(Vec2::new(0., 0.5), &block), None);
+        assert_eq!(circle(1., -1., 0.2).sweep(Vec2::ZERO, &block), None);
+    }
+
+    #[test]
+    fn sweep_overlapping() {
+        let block = rect(0., 0., 4., 2.);
+        let ball = circle(1., -0.1, 0.2);
+
+        // Moving further in hits immediately, moving out is free
+        assert_hit(
+            ball.sweep(Vec2::new(0., 1.), &block),
+            0.,
+            Vec2::NEG_Y,
+            Vec2::new(1., 0.),
+        );
+        assert_eq!(ball.sweep(Vec2::new(0., -1.), &block), None);
+    }
+
+    #[test]
+    fn sweep_circle_circle() {
+        assert_hit(
+            circle(-3., 0., 1.).sweep(Vec2::new(4., 0.), &circle(0., 0., 1.)),
+            0.25,
+            Vec2::NEG_X,
+            Vec2::new(-1., 0.),
+        );
+
+        assert_eq!(
+            circle(-3., 3., 1.).sweep(Vec2::new(4., 0.), &circle(0., 0., 1.)),
+            None
+        );
+    }
+
+    #[test]
+    fn sweep_rect_rect() {
+        assert_hit(
+            rect(-3., 0., 1., 1.).sweep(Vec2::new(4., 0.), &rect(0., 0., 4., 2.)),
+            0.5,
+            Vec2::NEG_X,
+            Vec2::new(0., 0.5),
+        );
+
+        assert_eq!(
+            rect(-3., 3., 1., 1.).sweep(Vec2::new(4., 0.), &rect(0., 0., 4., 2.)),
+            None
+        );
+    }
+
+    #[test]
+    fn sweep_rect_circle() {
+        // The rectangle moving right into a ball resting against its path
+        assert_hit(
+            rect(-3., 0., 1., 2.).sweep(Vec2::new(4., 0.), &circle(1., 1., 0.5)),
+            0.625,
+            Vec2::NEG_X,
+            Vec2::new(0.5, 1.),
+        );
+    }
+
+    #[test]
+    fn move_and_bounce_resolves_every_hit() {
+        let mut world = World::default();
+
+        // Ball heading into a corner made of two walls
+        let ball = world.push(Object {
+            position: Vec2::new(1., 1.),
+            shape: Shape::Circle { radius: 0.5 },
+            ..Default::default()
+        });
+
+        let right = world.push(Object {
+            position: Vec2::new(4., -10.),
+            shape: Shape::Rectangle {
+                width: 1.,
+                height: 20.,
+            },
+            ..Default::default()
+        });
+
+        let floor = world.push(Object {
+            position: Vec2::new(-10., 4.),
+            shape: Shape::Rectangle {
+                width: 20.,
+                height: 1.,
+            },
+            ..Default::default()
+        });
+
+        // Far enough to hit both walls within a single frame
+        let mut velocity = Vec2::new(10., 10.);
+        let hits = world.move_and_bounce(&ball, &mut velocity, 0.5, |_| true);
+
+        let hit_objects: Vec<ObjectIndex> = hits.iter().map(|(object, _)| *object).collect();
+        assert_eq!(hit_objects.len(), 2);
+        assert!(hit_objects.contains(&right) && hit_objects.contains(&floor));
+
+        // Bounced back out of the corner, the full distance travelled
+        assert!(velocity.abs_diff_eq(Vec2::new(-10., -10.), EPSILON));
+
+        let position = world.position(&ball).unwrap();
+        assert!(
+            position.abs_diff_eq(Vec2::new(1., 1.), 1e-3),
+            "ball ended at {position}"
+        );
+
+        // Filtered objects are ignored
+        let mut velocity = Vec2::new(10., 0.);
+        let hits = world.move_and_bounce(&ball, &mut velocity, 0.5, |object| object.index != right);
+        assert!(hits.is_empty());
+        assert!(velocity.abs_diff_eq(Vec2::new(10., 0.), EPSILON));
+    }
+}
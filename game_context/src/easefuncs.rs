@@ -1,9 +1,13 @@
 //! Various easing functions
 
 use std::f32::consts::PI;
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+use crate::*;
+
+#[derive(Debug, Clone, Default)]
 pub enum EaseFunc {
+    #[default]
     Linear,
     SmoothStop2,
     SmoothStop3,
@@ -16,15 +20,345 @@ pub enum EaseFunc {
     SmoothStep2,
     SmoothStep3,
     SmoothStep4,
+    SmoothStep5,
+    SineStart,
+    SineStop,
+    SineStep,
+    ExpoStart,
+    ExpoStop,
+    ExpoStep,
+    CircStart,
+    CircStop,
+    CircStep,
+    BackStart {
+        // How far to pull back past the start
+        overshoot: f32,
+    },
+    BackStop {
+        // How far to shoot past the target
+        overshoot: f32,
+    },
+    BackStep {
+        // How far to pull back past the start and shoot past the target
+        overshoot: f32,
+    },
+    ElasticStart {
+        // Amount of elastic to add
+        elastic: f32,
+    },
     ElasticStop {
         // Amount of elastic to add
         elastic: f32,
     },
+    ElasticStep {
+        // Amount of elastic to add
+        elastic: f32,
+    },
+    BounceStart {
+        // Height of each bounce relative to the previous one is `bounciness^2`
+        bounciness: f32,
+
+        // Number of bounces after the first impact
+        bounces: u32,
+    },
+    BounceStop {
+        // Height of each bounce relative to the previous one is `bounciness^2`
+        bounciness: f32,
+
+        // Number of bounces after the first impact
+        bounces: u32,
+    },
+    BounceStep {
+        // Height of each bounce relative to the previous one is `bounciness^2`
+        bounciness: f32,
+
+        // Number of bounces after the first impact
+        bounces: u32,
+    },
+    CubicBezier {
+        // The control points of a CSS `cubic-bezier(x1, y1, x2, y2)`. The x coordinates
+        // are clamped to 0..=1
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    Keyframes {
+        // Keyframes sorted by time, interpolated with cubic Hermite splines
+        keys: Arc<[Keyframe]>,
+    },
+    Reverse {
+        // Ease played backwards and flipped, turning a start into a stop
+        ease: Arc<EaseFunc>,
+    },
+    Mirror {
+        // Ease played forwards for the first half and backwards for the second,
+        // returning to the start
+        ease: Arc<EaseFunc>,
+    },
+    Blend {
+        // Ease used at a weight of 0
+        a: Arc<EaseFunc>,
+
+        // Ease used at a weight of 1
+        b: Arc<EaseFunc>,
+
+        // How much of `b` to mix into `a`
+        weight: f32,
+    },
+    Crossfade {
+        // Ease used at the start
+        a: Arc<EaseFunc>,
+
+        // Ease faded into over the course of the animation
+        b: Arc<EaseFunc>,
+    },
+    Chain {
+        // Ease covering `0..split` of both time and progress
+        first: Arc<EaseFunc>,
+
+        // Ease covering the rest of both time and progress
+        second: Arc<EaseFunc>,
+
+        // The time where `second` takes over, in `0..=1`
+        split: f32,
+    },
+    Scale {
+        // Ease whose output is remapped
+        ease: Arc<EaseFunc>,
+
+        // Multiplier for the output
+        scale: f32,
+
+        // Added to the output after scaling
+        offset: f32,
+    },
+}
+
+/// Elastic stop with a configurable amount of elastic
+fn elastic_stop(t: f32, elastic: f32) -> f32 {
+    // const c4 = (2 * Math.PI) / 3;
+    // return x === 0
+    //   ? 0
+    //   : x === 1
+    //   ? 1
+    //   : Math.pow(2, -10 * x) * Math.sin((x * 10 - 0.75) * c4) + 1;
+    const C4: f32 = (2. * PI) / 3.;
+
+    let is_zero = (t == 0.) as u32 as f32;
+    let is_one = (t == 1.) as u32 as f32;
+    let is_middle = (t != 0. && t != 1.) as u32 as f32;
+
+    is_zero * 0.
+        + is_one * 1.
+        + is_middle * (2.0_f32.powf(-10. * t) * ((t * elastic - 0.75) * C4).sin() + 1.0)
+}
+
+/// Bounce stop of a ball dropped onto the target, losing speed on every impact.
+/// A `bounciness` of 0.5 with 3 bounces is the classic `easeOutBounce`.
+fn bounce_stop(t: f32, bounciness: f32, bounces: u32) -> f32 {
+    // The first drop takes one unit of time and each bounce `2 * bounciness^n` units
+    let total: f32 = 1.
+        + (1..=bounces)
+            .map(|n| 2. * bounciness.powi(n as i32))
+            .sum::<f32>();
+    let mut x = t * total;
+
+    if x < 1. {
+        return x * x;
+    }
+
+    x -= 1.;
+
+    for n in 1..=bounces {
+        let half = bounciness.powi(n as i32);
+
+        if x < 2. * half {
+            // Parabola reaching `bounciness^2n` below the target at its peak
+            let u = x - half;
+            return 1. - (half * half - u * u);
+        }
+
+        x -= 2. * half;
+    }
+
+    1.
+}
+
+/// Solve a CSS `cubic-bezier(x1, y1, x2, y2)` for the y at the given x
+fn cubic_bezier(x: f32, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    const EPSILON: f32 = 1e-6;
+
+    // Coordinate of the curve with the given control points at `s`
+    let bezier = |p1: f32, p2: f32, s: f32| {
+        let inv = 1. - s;
+        3. * inv * inv * s * p1 + 3. * inv * s * s * p2 + s * s * s
+    };
+
+    // Slope of the curve coordinate with the given control points at `s`
+    let slope = |p1: f32, p2: f32, s: f32| {
+        let inv = 1. - s;
+        3. * inv * inv * p1 + 6. * inv * s * (p2 - p1) + 3. * s * s * (1. - p2)
+    };
+
+    let (x1, x2) = (x1.clamp(0., 1.), x2.clamp(0., 1.));
+    let x = x.clamp(0., 1.);
+
+    // Newton's method converges quickly unless the slope is close to flat
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < EPSILON {
+            return bezier(y1, y2, s);
+        }
+
+        let dx = slope(x1, x2, s);
+        if dx.abs() < EPSILON {
+            break;
+        }
+
+        // Stay on the curve segment, outside of it x is no longer monotonic in s
+        s = (s - error / dx).clamp(0., 1.);
+    }
+
+    // Fall back to bisection since x is monotonic in s
+    let (mut low, mut high) = (0., 1.);
+    s = x;
+    for _ in 0..64 {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < EPSILON {
+            break;
+        }
+
+        if error > 0. {
+            high = s;
+        } else {
+            low = s;
+        }
+
+        s = (low + high) / 2.;
+    }
+
+    bezier(y1, y2, s)
 }
 
 impl EaseFunc {
-    /// Calcuate the result based on the given `t`
+    /// The `overshoot` of the classic back eases
+    pub const BACK_OVERSHOOT: f32 = 1.70158;
+
+    /// The `elastic` of the classic elastic eases
+    pub const ELASTIC: f32 = 10.;
+
+    /// The `bounciness` of the classic bounce eases
+    pub const BOUNCINESS: f32 = 0.5;
+
+    /// The `bounces` of the classic bounce eases
+    pub const BOUNCES: u32 = 3;
+}
+
+impl EaseFunc {
+    /// Name of the variant, without its parameters
+    pub fn name(&self) -> &'static str {
+        match self {
+            EaseFunc::Linear => "Linear",
+            EaseFunc::SmoothStop2 => "SmoothStop2",
+            EaseFunc::SmoothStop3 => "SmoothStop3",
+            EaseFunc::SmoothStop4 => "SmoothStop4",
+            EaseFunc::SmoothStop5 => "SmoothStop5",
+            EaseFunc::SmoothStart2 => "SmoothStart2",
+            EaseFunc::SmoothStart3 => "SmoothStart3",
+            EaseFunc::SmoothStart4 => "SmoothStart4",
+            EaseFunc::SmoothStart5 => "SmoothStart5",
+            EaseFunc::SmoothStep2 => "SmoothStep2",
+            EaseFunc::SmoothStep3 => "SmoothStep3",
+            EaseFunc::SmoothStep4 => "SmoothStep4",
+            EaseFunc::SmoothStep5 => "SmoothStep5",
+            EaseFunc::SineStart => "SineStart",
+            EaseFunc::SineStop => "SineStop",
+            EaseFunc::SineStep => "SineStep",
+            EaseFunc::ExpoStart => "ExpoStart",
+            EaseFunc::ExpoStop => "ExpoStop",
+            EaseFunc::ExpoStep => "ExpoStep",
+            EaseFunc::CircStart => "CircStart",
+            EaseFunc::CircStop => "CircStop",
+            EaseFunc::CircStep => "CircStep",
+            EaseFunc::BackStart { .. } => "BackStart",
+            EaseFunc::BackStop { .. } => "BackStop",
+            EaseFunc::BackStep { .. } => "BackStep",
+            EaseFunc::ElasticStart { .. } => "ElasticStart",
+            EaseFunc::ElasticStop { .. } => "ElasticStop",
+            EaseFunc::ElasticStep { .. } => "ElasticStep",
+            EaseFunc::BounceStart { .. } => "BounceStart",
+            EaseFunc::BounceStop { .. } => "BounceStop",
+            EaseFunc::BounceStep { .. } => "BounceStep",
+            EaseFunc::CubicBezier { .. } => "CubicBezier",
+            EaseFunc::Keyframes { .. } => "Keyframes",
+            EaseFunc::Reverse { .. } => "Reverse",
+            EaseFunc::Mirror { .. } => "Mirror",
+            EaseFunc::Blend { .. } => "Blend",
+            EaseFunc::Crossfade { .. } => "Crossfade",
+            EaseFunc::Chain { .. } => "Chain",
+            EaseFunc::Scale { .. } => "Scale",
+        }
+    }
+
+    /// Play this ease backwards and flipped, so `SmoothStop3` becomes `SmoothStart3`
+    pub fn reverse(self) -> Self {
+        EaseFunc::Reverse {
+            ease: Arc::new(self),
+        }
+    }
+
+    /// Play this ease to the target and back within the same duration
+    pub fn mirror(self) -> Self {
+        EaseFunc::Mirror {
+            ease: Arc::new(self),
+        }
+    }
+
+    /// Mix `other` into this ease by `weight`
+    pub fn blend(self, other: EaseFunc, weight: f32) -> Self {
+        EaseFunc::Blend {
+            a: Arc::new(self),
+            b: Arc::new(other),
+            weight,
+        }
+    }
+
+    /// Fade from this ease into `other` over the course of the animation
+    pub fn crossfade(self, other: EaseFunc) -> Self {
+        EaseFunc::Crossfade {
+            a: Arc::new(self),
+            b: Arc::new(other),
+        }
+    }
+
+    /// Use this ease until `split` and `other` for the rest of the animation
+    pub fn chain(self, other: EaseFunc, split: f32) -> Self {
+        EaseFunc::Chain {
+            first: Arc::new(self),
+            second: Arc::new(other),
+            split,
+        }
+    }
+
+    /// Remap the output of this ease to `value * scale + offset`
+    pub fn scale(self, scale: f32, offset: f32) -> Self {
+        EaseFunc::Scale {
+            ease: Arc::new(self),
+            scale,
+            offset,
+        }
+    }
+}
+
+impl EaseFunc {
+    /// Calcuate the result based on the given `t`. A NaN `t` is treated as the start of
+    /// the ease.
     pub fn calculate(&self, t: f32) -> f32 {
+        // Like before the first keyframe, so a NaN never leaks into the animated value
+        let t = if t.is_nan() { 0. } else { t };
+
         match self {
             EaseFunc::Linear => t,
             EaseFunc::SmoothStop2 => 1. - ((1. - t) * (1. - t)),
@@ -37,39 +371,608 @@ impl EaseFunc {
             EaseFunc::SmoothStart5 => t * t * t * t * t,
             EaseFunc::SmoothStep2 => {
                 // return x < 0.5 ? 2 * x * x : 1 - Math.pow(-2 * x + 2, 2) / 2;
-                let t1 = (t < 0.5) as u32 as f32 * 2. * t * t + (t >= 0.5) as u32 as f32;
-                let t2 = ((-2. * t + 2.) * (-2. * t + 2.)) / 2.;
+                let (lt, ge) = ((t < 0.5) as u32 as f32, (t >= 0.5) as u32 as f32);
+                let t1 = lt * 2. * t * t + ge;
+                let t2 = ge * ((-2. * t + 2.) * (-2. * t + 2.)) / 2.;
                 t1 - t2
             }
             EaseFunc::SmoothStep3 => {
                 // return x < 0.5 ? 4 * x * x * x : 1 - Math.pow(-2 * x + 2, 3) / 2;
-                let t1 = (t < 0.5) as u32 as f32 * 4. * t * t + (t >= 0.5) as u32 as f32;
-                let t2 = ((-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.)) / 2.;
+                let (lt, ge) = ((t < 0.5) as u32 as f32, (t >= 0.5) as u32 as f32);
+                let t1 = lt * 4. * t * t * t + ge;
+                let t2 = ge * ((-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.)) / 2.;
                 t1 - t2
             }
             EaseFunc::SmoothStep4 => {
-                // return x < 0.5 ? 8 * x * x * x : 1 - Math.pow(-2 * x + 2, 4) / 2;
-                let t1 = (t < 0.5) as u32 as f32 * 8. * t * t + (t >= 0.5) as u32 as f32;
-                let t2 = ((-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.)) / 2.;
+                // return x < 0.5 ? 8 * x * x * x * x : 1 - Math.pow(-2 * x + 2, 4) / 2;
+                let (lt, ge) = ((t < 0.5) as u32 as f32, (t >= 0.5) as u32 as f32);
+                let t1 = lt * 8. * t * t * t * t + ge;
+                let t2 =
+                    ge * ((-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.) * (-2. * t + 2.)) / 2.;
+                t1 - t2
+            }
+            EaseFunc::SmoothStep5 => {
+                // return x < 0.5 ? 16 * x * x * x * x * x : 1 - Math.pow(-2 * x + 2, 5) / 2;
+                let (lt, ge) = ((t < 0.5) as u32 as f32, (t >= 0.5) as u32 as f32);
+                let t1 = lt * 16. * t * t * t * t * t + ge;
+                let t2 = ge * (-2. * t + 2.).powi(5) / 2.;
                 t1 - t2
             }
-            EaseFunc::ElasticStop { elastic } => {
-                // const c4 = (2 * Math.PI) / 3;
-                // return x === 0
-                //   ? 0
-                //   : x === 1
-                //   ? 1
-                //   : Math.pow(2, -10 * x) * Math.sin((x * 10 - 0.75) * c4) + 1;
-                const C4: f32 = (2. * PI) / 3.;
+            EaseFunc::SineStart => 1. - (t * PI / 2.).cos(),
+            EaseFunc::SineStop => (t * PI / 2.).sin(),
+            EaseFunc::SineStep => -((PI * t).cos() - 1.) / 2.,
+            EaseFunc::ExpoStart => {
+                if t <= 0. {
+                    0.
+                } else {
+                    2.0_f32.powf(10. * t - 10.)
+                }
+            }
+            EaseFunc::ExpoStop => {
+                if t >= 1. {
+                    1.
+                } else {
+                    1. - 2.0_f32.powf(-10. * t)
+                }
+            }
+            EaseFunc::ExpoStep => {
+                if t <= 0. {
+                    0.
+                } else if t >= 1. {
+                    1.
+                } else if t < 0.5 {
+                    2.0_f32.powf(20. * t - 10.) / 2.
+                } else {
+                    (2. - 2.0_f32.powf(-20. * t + 10.)) / 2.
+                }
+            }
+            EaseFunc::CircStart => 1. - (1. - t * t).max(0.).sqrt(),
+            EaseFunc::CircStop => (1. - (t - 1.) * (t - 1.)).max(0.).sqrt(),
+            EaseFunc::CircStep => {
+                if t < 0.5 {
+                    (1. - (1. - (2. * t) * (2. * t)).max(0.).sqrt()) / 2.
+                } else {
+                    ((1. - (-2. * t + 2.) * (-2. * t + 2.)).max(0.).sqrt() + 1.) / 2.
+                }
+            }
+            EaseFunc::BackStart { overshoot } => {
+                // return c3 * x * x * x - c1 * x * x;
+                (overshoot + 1.) * t * t * t - overshoot * t * t
+            }
+            EaseFunc::BackStop { overshoot } => {
+                // return 1 + c3 * Math.pow(x - 1, 3) + c1 * Math.pow(x - 1, 2);
+                let u = t - 1.;
+                1. + (overshoot + 1.) * u * u * u + overshoot * u * u
+            }
+            EaseFunc::BackStep { overshoot } => {
+                // const c2 = c1 * 1.525;
+                let c2 = overshoot * 1.525;
 
-                let is_zero = (t == 0.) as u32 as f32;
-                let is_one = (t == 1.) as u32 as f32;
-                let is_middle = (t != 0. && t != 1.) as u32 as f32;
+                if t < 0.5 {
+                    ((2. * t) * (2. * t) * ((c2 + 1.) * 2. * t - c2)) / 2.
+                } else {
+                    let u = 2. * t - 2.;
+                    (u * u * ((c2 + 1.) * u + c2) + 2.) / 2.
+                }
+            }
+            EaseFunc::ElasticStart { elastic } => 1. - elastic_stop(1. - t, *elastic),
+            EaseFunc::ElasticStop { elastic } => elastic_stop(t, *elastic),
+            EaseFunc::ElasticStep { elastic } => {
+                if t < 0.5 {
+                    (1. - elastic_stop(1. - 2. * t, *elastic)) / 2.
+                } else {
+                    (1. + elastic_stop(2. * t - 1., *elastic)) / 2.
+                }
+            }
+            EaseFunc::BounceStart {
+                bounciness,
+                bounces,
+            } => 1. - bounce_stop(1. - t, *bounciness, *bounces),
+            EaseFunc::BounceStop {
+                bounciness,
+                bounces,
+            } => bounce_stop(t, *bounciness, *bounces),
+            EaseFunc::BounceStep {
+                bounciness,
+                bounces,
+            } => {
+                if t < 0.5 {
+                    (1. - bounce_stop(1. - 2. * t, *bounciness, *bounces)) / 2.
+                } else {
+                    (1. + bounce_stop(2. * t - 1., *bounciness, *bounces)) / 2.
+                }
+            }
+            EaseFunc::CubicBezier { x1, y1, x2, y2 } => cubic_bezier(t, *x1, *y1, *x2, *y2),
+            EaseFunc::Keyframes { keys } => keyframes::hermite(keys, t),
+            EaseFunc::Reverse { ease } => 1. - ease.calculate(1. - t),
+            EaseFunc::Mirror { ease } => {
+                if t < 0.5 {
+                    ease.calculate(2. * t)
+                } else {
+                    ease.calculate(2. - 2. * t)
+                }
+            }
+            EaseFunc::Blend { a, b, weight } => {
+                let a = a.calculate(t);
+                a + (b.calculate(t) - a) * weight
+            }
+            EaseFunc::Crossfade { a, b } => {
+                let a = a.calculate(t);
+                a + (b.calculate(t) - a) * t
+            }
+            EaseFunc::Chain {
+                first,
+                second,
+                split,
+            } => {
+                let split = split.clamp(0., 1.);
+
+                // Scale each half so the chain is continuous at the split
+                if t < split {
+                    first.calculate(t / split) * split
+                } else if split < 1. {
+                    split + second.calculate((t - split) / (1. - split)) * (1. - split)
+                } else {
+                    first.calculate(t)
+                }
+            }
+            EaseFunc::Scale {
+                ease,
+                scale,
+                offset,
+            } => ease.calculate(t) * scale + offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyframes::tests::overshoot_keys;
+
+    /// Number of samples taken over `0..=1`
+    const SAMPLES: usize = 10_000;
+
+    /// Tolerance for values that should match exactly
+    const EPSILON: f32 = 1e-4;
+
+    /// Every ease function, with the parameterized ones at several parameters
+    fn all_eases() -> Vec<EaseFunc> {
+        let mut eases = Vec::new();
+
+        for overshoot in [0., 0.5, EaseFunc::BACK_OVERSHOOT, 3.] {
+            eases.push(EaseFunc::BackStart { overshoot });
+            eases.push(EaseFunc::BackStop { overshoot });
+            eases.push(EaseFunc::BackStep { overshoot });
+        }
+
+        for elastic in [2., 4., EaseFunc::ELASTIC, 15.] {
+            eases.push(EaseFunc::ElasticStart { elastic });
+            eases.push(EaseFunc::ElasticStop { elastic });
+            eases.push(EaseFunc::ElasticStep { elastic });
+        }
+
+        for (bounciness, bounces) in [
+            (0.2, 1),
+            (EaseFunc::BOUNCINESS, EaseFunc::BOUNCES),
+            (0.7, 6),
+        ] {
+            eases.push(EaseFunc::BounceStart {
+                bounciness,
+                bounces,
+            });
+            eases.push(EaseFunc::BounceStop {
+                bounciness,
+                bounces,
+            });
+            eases.push(EaseFunc::BounceStep {
+                bounciness,
+                bounces,
+            });
+        }
+
+        // CSS `ease-in-out` and `ease-out-back`
+        eases.push(EaseFunc::CubicBezier {
+            x1: 0.42,
+            y1: 0.,
+            x2: 0.58,
+            y2: 1.,
+        });
+        eases.push(EaseFunc::CubicBezier {
+            x1: 0.34,
+            y1: 1.56,
+            x2: 0.64,
+            y2: 1.,
+        });
+
+        eases.push(EaseFunc::Keyframes {
+            keys: Arc::from(overshoot_keys()),
+        });
+
+        // Combinators that keep the endpoints
+        eases.push(EaseFunc::SmoothStop3.reverse());
+        eases.push(
+            EaseFunc::BounceStep {
+                bounciness: EaseFunc::BOUNCINESS,
+                bounces: EaseFunc::BOUNCES,
+            }
+            .reverse(),
+        );
+        eases.push(EaseFunc::SmoothStop2.blend(EaseFunc::BackStop { overshoot: 2. }, 0.3));
+        eases.push(EaseFunc::SmoothStart3.crossfade(EaseFunc::SmoothStop3));
+        eases.push(EaseFunc::SmoothStart2.chain(EaseFunc::ElasticStop { elastic: 4. }, 0.5));
+        eases.push(EaseFunc::Linear.chain(EaseFunc::SmoothStop2, 0.));
+        eases.push(EaseFunc::Linear.chain(EaseFunc::SmoothStop2, 1.));
+        eases.push(EaseFunc::ExpoStart.scale(1., 0.));
+
+        eases.extend(MONOTONIC.iter().cloned());
+        eases
+    }
+
+    /// Ease functions that never move backwards
+    const MONOTONIC: &[EaseFunc] = &[
+        EaseFunc::Linear,
+        EaseFunc::SmoothStop2,
+        EaseFunc::SmoothStop3,
+        EaseFunc::SmoothStop4,
+        EaseFunc::SmoothStop5,
+        EaseFunc::SmoothStart2,
+        EaseFunc::SmoothStart3,
+        EaseFunc::SmoothStart4,
+        EaseFunc::SmoothStart5,
+        EaseFunc::SmoothStep2,
+        EaseFunc::SmoothStep3,
+        EaseFunc::SmoothStep4,
+        EaseFunc::SmoothStep5,
+        EaseFunc::SineStart,
+        EaseFunc::SineStop,
+        EaseFunc::SineStep,
+        EaseFunc::ExpoStart,
+        EaseFunc::ExpoStop,
+        EaseFunc::ExpoStep,
+        EaseFunc::CircStart,
+        EaseFunc::CircStop,
+        EaseFunc::CircStep,
+    ];
+
+    /// Make sure a new variant is added to the suite. Does not need to be called.
+    #[allow(dead_code)]
+    fn covered(ease: EaseFunc) {
+        match ease {
+            EaseFunc::Linear
+            | EaseFunc::SmoothStop2
+            | EaseFunc::SmoothStop3
+            | EaseFunc::SmoothStop4
+            | EaseFunc::SmoothStop5
+            | EaseFunc::SmoothStart2
+            | EaseFunc::SmoothStart3
+            | EaseFunc::SmoothStart4
+            | EaseFunc::SmoothStart5
+            | EaseFunc::SmoothStep2
+            | EaseFunc::SmoothStep3
+            | EaseFunc::SmoothStep4
+            | EaseFunc::SmoothStep5
+            | EaseFunc::SineStart
+            | EaseFunc::SineStop
+            | EaseFunc::SineStep
+            | EaseFunc::ExpoStart
+            | EaseFunc::ExpoStop
+            | EaseFunc::ExpoStep
+            | EaseFunc::CircStart
+            | EaseFunc::CircStop
+            | EaseFunc::CircStep
+            | EaseFunc::BackStart { .. }
+            | EaseFunc::BackStop { .. }
+            | EaseFunc::BackStep { .. }
+            | EaseFunc::ElasticStart { .. }
+            | EaseFunc::ElasticStop { .. }
+            | EaseFunc::ElasticStep { .. }
+            | EaseFunc::BounceStart { .. }
+            | EaseFunc::BounceStop { .. }
+            | EaseFunc::BounceStep { .. }
+            | EaseFunc::CubicBezier { .. }
+            | EaseFunc::Keyframes { .. }
+            | EaseFunc::Reverse { .. }
+            | EaseFunc::Mirror { .. }
+            | EaseFunc::Blend { .. }
+            | EaseFunc::Crossfade { .. }
+            | EaseFunc::Chain { .. }
+            | EaseFunc::Scale { .. } => {}
+        }
+    }
+
+    /// Sample `ease` evenly over `0..=1`
+    fn samples(ease: &EaseFunc) -> impl Iterator<Item = (f32, f32)> + '_ {
+        (0..=SAMPLES).map(move |i| {
+            let t = i as f32 / SAMPLES as f32;
+            (t, ease.calculate(t))
+        })
+    }
+
+    #[test]
+    fn endpoints() {
+        for ease in all_eases() {
+            let start = ease.calculate(0.);
+            let end = ease.calculate(1.);
+
+            assert!(start.abs() < EPSILON, "{ease:?} starts at {start}");
+            assert!((end - 1.).abs() < EPSILON, "{ease:?} ends at {end}");
+        }
+    }
+
+    #[test]
+    fn finite() {
+        for ease in all_eases() {
+            for (t, value) in samples(&ease) {
+                assert!(value.is_finite(), "{ease:?} is {value} at {t}");
+            }
+
+            let value = ease.calculate(f32::NAN);
+            assert_eq!(value, ease.calculate(0.), "{ease:?} is {value} at NaN");
+        }
+    }
+
+    #[test]
+    fn continuous_at_seam() {
+        for ease in all_eases() {
+            let below = ease.calculate(0.5 - 1e-6);
+            let at = ease.calculate(0.5);
+            let above = ease.calculate(0.5 + 1e-6);
+
+            // Circular eases are vertical at the seam, so allow for their steep slope
+            assert!((below - at).abs() < 5e-3, "{ease:?} jumps below 0.5");
+            assert!((above - at).abs() < 5e-3, "{ease:?} jumps above 0.5");
+        }
+    }
+
+    #[test]
+    fn continuous() {
+        for ease in all_eases() {
+            let values: Vec<(f32, f32)> = samples(&ease).collect();
+
+            // Circular eases are vertical at their ends, so allow for their steep slope
+            for pair in values.windows(2) {
+                let [(t0, v0), (t1, v1)] = [pair[0], pair[1]];
+                assert!(
+                    (v1 - v0).abs() < 0.02,
+                    "{ease:?} jumps from {v0} at {t0} to {v1} at {t1}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn monotonic() {
+        for ease in MONOTONIC {
+            let values: Vec<(f32, f32)> = samples(ease).collect();
+
+            for pair in values.windows(2) {
+                let [(t0, v0), (t1, v1)] = [pair[0], pair[1]];
+                assert!(
+                    v1 >= v0 - 1e-6,
+                    "{ease:?} falls from {v0} at {t0} to {v1} at {t1}"
+                );
+            }
+
+            for (t, value) in &values {
+                assert!(
+                    (-EPSILON..=1. + EPSILON).contains(value),
+                    "{ease:?} leaves 0..=1 with {value} at {t}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn step_passes_through_middle() {
+        for ease in all_eases() {
+            // Combinators are named after themselves, so only plain steps are checked
+            if !ease.name().contains("Step") {
+                continue;
+            }
+
+            let middle = ease.calculate(0.5);
+            assert!(
+                (middle - 0.5).abs() < EPSILON,
+                "{ease:?} is {middle} at 0.5"
+            );
+        }
+    }
+
+    #[test]
+    fn start_mirrors_stop() {
+        let pairs = [
+            (EaseFunc::SmoothStart2, EaseFunc::SmoothStop2),
+            (EaseFunc::SmoothStart3, EaseFunc::SmoothStop3),
+            (EaseFunc::SmoothStart4, EaseFunc::SmoothStop4),
+            (EaseFunc::SmoothStart5, EaseFunc::SmoothStop5),
+            (EaseFunc::SineStart, EaseFunc::SineStop),
+            (EaseFunc::ExpoStart, EaseFunc::ExpoStop),
+            (EaseFunc::CircStart, EaseFunc::CircStop),
+            (
+                EaseFunc::BackStart { overshoot: 2. },
+                EaseFunc::BackStop { overshoot: 2. },
+            ),
+            (
+                EaseFunc::ElasticStart { elastic: 10. },
+                EaseFunc::ElasticStop { elastic: 10. },
+            ),
+            (
+                EaseFunc::BounceStart {
+                    bounciness: 0.5,
+                    bounces: 3,
+                },
+                EaseFunc::BounceStop {
+                    bounciness: 0.5,
+                    bounces: 3,
+                },
+            ),
+        ];
 
-                is_zero * 0.
-                    + is_one * 1.
-                    + is_middle * (2.0_f32.powf(-10. * t) * ((t * elastic - 0.75) * C4).sin() + 1.0)
+        for (start, stop) in pairs {
+            for (t, value) in samples(&start) {
+                let mirrored = 1. - stop.calculate(1. - t);
+                assert!(
+                    (value - mirrored).abs() < 1e-3,
+                    "{start:?} is {value} at {t} but {stop:?} mirrors to {mirrored}"
+                );
             }
         }
     }
+
+    #[test]
+    fn smooth_step_matches_formula() {
+        for i in 0..=SAMPLES {
+            let t = i as f32 / SAMPLES as f32;
+
+            for (ease, power) in [
+                (EaseFunc::SmoothStep2, 2),
+                (EaseFunc::SmoothStep3, 3),
+                (EaseFunc::SmoothStep4, 4),
+                (EaseFunc::SmoothStep5, 5),
+            ] {
+                // x < 0.5 ? 2^(n-1) * x^n : 1 - (-2x + 2)^n / 2
+                let expected = if t < 0.5 {
+                    2.0_f32.powi(power - 1) * t.powi(power)
+                } else {
+                    1. - (-2. * t + 2.).powi(power) / 2.
+                };
+
+                let value = ease.calculate(t);
+                assert!(
+                    (value - expected).abs() < 1e-5,
+                    "{ease:?} is {value} at {t}, expected {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_solves_x() {
+        let (x1, y1, x2, y2) = (0.25, 0.1, 0.25, 1.);
+        let ease = EaseFunc::CubicBezier { x1, y1, x2, y2 };
+
+        // Sample the curve by its parameter and check the solver finds the same point
+        for i in 0..=100 {
+            let s = i as f32 / 100.;
+            let inv = 1. - s;
+            let x = 3. * inv * inv * s * x1 + 3. * inv * s * s * x2 + s * s * s;
+            let y = 3. * inv * inv * s * y1 + 3. * inv * s * s * y2 + s * s * s;
+
+            let value = ease.calculate(x);
+            assert!((value - y).abs() < 1e-4, "{value} at {x}, expected {y}");
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_linear() {
+        let ease = EaseFunc::CubicBezier {
+            x1: 0.,
+            y1: 0.,
+            x2: 1.,
+            y2: 1.,
+        };
+
+        for (t, value) in samples(&ease) {
+            assert!((value - t).abs() < 1e-4, "{value} at {t}");
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_extreme_control_points() {
+        let (x1, y1, x2, y2) = (0., 10., 1., -10.);
+        let ease = EaseFunc::CubicBezier { x1, y1, x2, y2 };
+
+        // The x slope vanishes at both ends while y swings far outside 0..=1
+        for i in 0..=100 {
+            let s = i as f32 / 100.;
+            let inv = 1. - s;
+            let x = 3. * inv * inv * s * x1 + 3. * inv * s * s * x2 + s * s * s;
+            let y = 3. * inv * inv * s * y1 + 3. * inv * s * s * y2 + s * s * s;
+
+            let value = ease.calculate(x);
+            assert!((value - y).abs() < 1e-2, "{value} at {x}, expected {y}");
+        }
+
+        for (t, value) in samples(&ease) {
+            assert!(value.is_finite(), "{value} at {t}");
+        }
+    }
+
+    #[test]
+    fn reverse_of_stop_is_start() {
+        for (start, stop) in [
+            (EaseFunc::SmoothStart3, EaseFunc::SmoothStop3),
+            (EaseFunc::SineStart, EaseFunc::SineStop),
+        ] {
+            let reversed = stop.reverse();
+
+            for (t, value) in samples(&reversed) {
+                let expected = start.calculate(t);
+                assert!(
+                    (value - expected).abs() < 1e-5,
+                    "{reversed:?} is {value} at {t}, expected {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_returns_to_start() {
+        let ease = EaseFunc::SmoothStop2.mirror();
+
+        assert!(ease.calculate(0.).abs() < EPSILON);
+        assert!((ease.calculate(0.5) - 1.).abs() < EPSILON);
+        assert!(ease.calculate(1.).abs() < EPSILON);
+
+        for (t, value) in samples(&ease) {
+            let other = ease.calculate(1. - t);
+            assert!(
+                (value - other).abs() < 1e-3,
+                "{value} at {t}, {other} at {}",
+                1. - t
+            );
+        }
+    }
+
+    #[test]
+    fn blend_weights() {
+        let (a, b) = (EaseFunc::SmoothStart2, EaseFunc::SmoothStop2);
+
+        for (t, value) in samples(&a.clone().blend(b.clone(), 0.)) {
+            assert!((value - a.calculate(t)).abs() < EPSILON, "{value} at {t}");
+        }
+
+        for (t, value) in samples(&a.clone().blend(b.clone(), 1.)) {
+            assert!((value - b.calculate(t)).abs() < EPSILON, "{value} at {t}");
+        }
+
+        let half = a.clone().blend(b.clone(), 0.5);
+        let value = half.calculate(0.25);
+        let expected = (a.calculate(0.25) + b.calculate(0.25)) / 2.;
+        assert!(
+            (value - expected).abs() < EPSILON,
+            "{value}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn chain_splits() {
+        let ease = EaseFunc::SmoothStart2.chain(EaseFunc::Linear, 0.4);
+
+        // First half is a shrunk SmoothStart2, second half a straight line to the target
+        assert!((ease.calculate(0.2) - 0.5 * 0.5 * 0.4).abs() < EPSILON);
+        assert!((ease.calculate(0.4) - 0.4).abs() < EPSILON);
+        assert!((ease.calculate(0.7) - 0.7).abs() < EPSILON);
+    }
+
+    #[test]
+    fn scale_remaps_output() {
+        let ease = EaseFunc::SmoothStop2.scale(0.5, 0.25);
+
+        for (t, value) in samples(&ease) {
+            let expected = EaseFunc::SmoothStop2.calculate(t) * 0.5 + 0.25;
+            assert!((value - expected).abs() < EPSILON, "{value} at {t}");
+        }
+    }
 }
@@ -0,0 +1,264 @@
+//! Hand-drawn easing curves made of keyframes, loaded from a data file
+//!
+//! A keyframe file has one `t value tangent` keyframe per line, sorted by `t`. Empty
+//! lines and everything after a `#` are ignored:
+//!
+//! ```text
+//! # Overshoot the target and settle back
+//! 0.0  0.0  0.0
+//! 0.6  1.1  0.0
+//! 1.0  1.0  0.0
+//! ```
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::*;
+
+/// A single point of a keyframe curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    /// Time of the keyframe in `0..=1`
+    pub t: f32,
+
+    /// Value of the curve at `t`
+    pub value: f32,
+
+    /// Slope of the curve at `t`
+    pub tangent: f32,
+}
+
+/// Errors returned when loading a keyframe curve
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyframeError {
+    /// The keyframe file could not be read
+    Io(String),
+
+    /// A line of the keyframe file is malformed
+    Parse { line: usize, message: String },
+
+    /// The curve has less than two keyframes
+    TooFewKeyframes,
+
+    /// The keyframe times are not strictly increasing
+    Unsorted { line: usize },
+}
+
+impl core::fmt::Display for KeyframeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            KeyframeError::Io(err) => write!(f, "Failed to read keyframes: {err}"),
+            KeyframeError::Parse { line, message } => write!(f, "Line {line}: {message}"),
+            KeyframeError::TooFewKeyframes => write!(f, "A curve needs at least two keyframes"),
+            KeyframeError::Unsorted { line } => {
+                write!(f, "Line {line}: keyframe is not after the previous one")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeyframeError {}
+
+/// Parse the keyframes of a curve from the keyframe file format
+pub fn parse_keyframes(text: &str) -> Result<Vec<Keyframe>, KeyframeError> {
+    let mut keys: Vec<Keyframe> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+
+        // Strip comments
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let numbers = line
+            .split_whitespace()
+            .map(|number| {
+                number
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(|| KeyframeError::Parse {
+                        line: line_number,
+                        message: format!("Invalid number {number:?}"),
+                    })
+            })
+            .collect::<Result<Vec<f32>, KeyframeError>>()?;
+
+        let [t, value, tangent] = numbers[..] else {
+            return Err(KeyframeError::Parse {
+                line: line_number,
+                message: format!(
+                    "Expected `t value tangent`, found {} numbers",
+                    numbers.len()
+                ),
+            });
+        };
+
+        if keys.last().is_some_and(|last| t <= last.t) {
+            return Err(KeyframeError::Unsorted { line: line_number });
+        }
+
+        keys.push(Keyframe { t, value, tangent });
+    }
+
+    if keys.len() < 2 {
+        return Err(KeyframeError::TooFewKeyframes);
+    }
+
+    Ok(keys)
+}
+
+/// Evaluate the curve through `keys` at `t` using cubic Hermite interpolation. The
+/// curve is flat before the first and after the last keyframe.
+pub(crate) fn hermite(keys: &[Keyframe], t: f32) -> f32 {
+    let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+        return t;
+    };
+
+    // NaN is treated as before the first keyframe so the search below always finds
+    // a keyframe before `t`
+    if t <= first.t || t.is_nan() {
+        return first.value;
+    }
+
+    if t >= last.t {
+        return last.value;
+    }
+
+    // Find the keyframes surrounding `t`
+    let next = keys.partition_point(|key| key.t <= t);
+    let (k0, k1) = (keys[next - 1], keys[next]);
+
+    let h = k1.t - k0.t;
+    let s = (t - k0.t) / h;
+    let (s2, s3) = (s * s, s * s * s);
+
+    let h00 = 2. * s3 - 3. * s2 + 1.;
+    let h10 = s3 - 2. * s2 + s;
+    let h01 = -2. * s3 + 3. * s2;
+    let h11 = s3 - s2;
+
+    h00 * k0.value + h10 * h * k0.tangent + h01 * k1.value + h11 * h * k1.tangent
+}
+
+/// A keyframe curve loaded from a file that is reloaded when the file changes
+#[derive(Debug, Clone)]
+pub struct KeyframeFile {
+    /// Path to the keyframe file
+    path: PathBuf,
+
+    /// The modified time of the file when it was last loaded
+    modified: Option<SystemTime>,
+
+    /// Was the file missing on the last reload, so it is only reported once
+    missing: bool,
+
+    /// The curve from the last successful load
+    ease: Option<EaseFunc>,
+}
+
+impl KeyframeFile {
+    /// Watch the keyframe file at `path`. Nothing is loaded until [`KeyframeFile::reload`].
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            missing: false,
+            ease: None,
+        }
+    }
+
+    /// Load the file again if it changed since the last call. Returns `true` if a new
+    /// curve was loaded. On error the previously loaded curve is kept.
+    pub fn reload(&mut self) -> Result<bool, KeyframeError> {
+        let modified = match std::fs::metadata(&self.path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            Err(_) if self.missing => return Ok(false),
+            Err(err) => {
+                self.missing = true;
+                return Err(KeyframeError::Io(format!("{}: {err}", self.path.display())));
+            }
+        };
+
+        self.missing = false;
+
+        // If the file hasn't been updated, no need to reload it
+        if self.modified == Some(modified) {
+            return Ok(false);
+        }
+
+        // Only report a broken file once per change
+        self.modified = Some(modified);
+
+        let text = std::fs::read_to_string(&self.path)
+            .map_err(|err| KeyframeError::Io(format!("{}: {err}", self.path.display())))?;
+
+        self.ease = Some(EaseFunc::Keyframes {
+            keys: Arc::from(parse_keyframes(&text)?),
+        });
+
+        Ok(true)
+    }
+
+    /// The curve from the last successful load
+    pub fn ease(&self) -> Option<&EaseFunc> {
+        self.ease.as_ref()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Keyframes shooting past the target and settling back
+    pub(crate) fn overshoot_keys() -> Vec<Keyframe> {
+        parse_keyframes("0 0 0\n0.6 1.1 0 # overshoot\n\n1 1 0").unwrap()
+    }
+
+    #[test]
+    fn curve_passes_through_keys() {
+        let keys = overshoot_keys();
+        let ease = EaseFunc::Keyframes {
+            keys: Arc::from(keys.clone()),
+        };
+
+        for key in keys {
+            let value = ease.calculate(key.t);
+            assert!((value - key.value).abs() < 1e-4, "{value} at {}", key.t);
+        }
+    }
+
+    #[test]
+    fn curve_is_flat_outside_keys() {
+        let keys = overshoot_keys();
+
+        assert_eq!(hermite(&keys, -1.), 0.);
+        assert_eq!(hermite(&keys, f32::NEG_INFINITY), 0.);
+        assert_eq!(hermite(&keys, f32::NAN), 0.);
+        assert_eq!(hermite(&keys, 2.), 1.);
+        assert_eq!(hermite(&keys, f32::INFINITY), 1.);
+    }
+
+    #[test]
+    fn bad_files_are_rejected() {
+        assert_eq!(
+            parse_keyframes("0 0 0"),
+            Err(KeyframeError::TooFewKeyframes)
+        );
+        assert_eq!(
+            parse_keyframes("0 0 0\n1 1 0\n0.5 0.5 0"),
+            Err(KeyframeError::Unsorted { line: 3 })
+        );
+        assert!(matches!(
+            parse_keyframes("0 0 0\n1 1"),
+            Err(KeyframeError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_keyframes("0 0 0\n1 one 0"),
+            Err(KeyframeError::Parse { line: 2, .. })
+        ));
+    }
+}
@@ -1,24 +1,43 @@
-pub use macroquad::math::Vec2;
+pub use macroquad::math::{Rect, Vec2};
 use macroquad::prelude::*;
 
 pub use macroquad::color::*;
-pub use macroquad::prelude::{KeyCode, TextParams};
+pub use macroquad::prelude::{DrawRectangleParams, KeyCode, TextParams};
 
 mod memory;
-pub use memory::{Allocation, Memory, MEMORY_BASE_ADDR, MEMORY_LENGTH};
+pub use memory::{
+    Allocation, AllocationRecord, Corruption, Memory, MemoryBackend, MemoryConfig, MemoryError,
+    TagStats, UsageWarning, CANARY_SIZE, MEMORY_BASE_ADDR, MEMORY_LENGTH, MEMORY_RESERVE,
+    MEMORY_WARN_THRESHOLD, PAGE_SIZE, RESUMED, UNTAGGED,
+};
+
+mod pool;
+pub use pool::*;
+
+mod snapshot;
+pub use snapshot::*;
 
 mod world;
 pub use world::*;
 
+mod collision;
+pub use collision::*;
+
 mod macroquad_funcs;
 pub use macroquad_funcs::*;
 
 mod easefuncs;
 pub use easefuncs::*;
 
+mod keyframes;
+pub use keyframes::*;
+
 mod animation;
 pub use animation::*;
 
+mod visualizer;
+pub use visualizer::*;
+
 /// The context of a game
 pub struct GameContext<'a> {
     /// Potential error when executing the game logic
@@ -32,6 +51,9 @@ pub struct GameContext<'a> {
 
     /// The time (in seconds) per frame for the game
     pub frame_time: f32,
+
+    /// Show the ease visualizer instead of the game
+    pub show_ease_visualizer: bool,
 }
 
 pub const BLOCKS_WIDTH: usize = 10;
@@ -39,16 +61,51 @@ pub const BLOCKS_HEIGHT: usize = 10;
 pub const SCREEN_WIDTH: f32 = 20.0;
 pub const SCREEN_HEIGHT: f32 = 20.0;
 
+/// Version of the layout of the data the game keeps in the [`Memory`]. Bump it whenever
+/// a type allocated there changes so a stale memory file isn't resumed.
+pub const MEMORY_SCHEMA: u64 = 1;
+
 // The game state data. This data is initialized in the game code itself.
 #[derive(Debug, Clone)]
 pub struct State {
     pub reset_initialized: bool,
-    pub blocks: [[bool; BLOCKS_WIDTH]; BLOCKS_HEIGHT],
-    pub ball: Vec2,
+    pub dropping: usize,
+    pub ball: ObjectIndex,
     pub ball_velocity: Vec2,
     pub platform: ObjectIndex,
     pub platform_width: f32,
-    pub platform_height: f32,
     pub stick: bool,
     pub world: World,
+    pub drop_curve: KeyframeFile,
+    pub ease_visualizer: EaseVisualizer,
+}
+
+impl Inspect for State {
+    fn fields(&self) -> Vec<(String, String)> {
+        let world = &self.world;
+        let animating = world.iter().filter(|object| object.animating).count();
+
+        vec![
+            ("dropping".to_string(), format!("{:?}", self.dropping)),
+            ("stick".to_string(), format!("{:?}", self.stick)),
+            (
+                "ball".to_string(),
+                format!("{:?}", world.position(&self.ball)),
+            ),
+            (
+                "ball_velocity".to_string(),
+                format!("{:?}", self.ball_velocity),
+            ),
+            (
+                "platform".to_string(),
+                format!("{:?}", world.position(&self.platform)),
+            ),
+            (
+                "platform_width".to_string(),
+                format!("{:?}", self.platform_width),
+            ),
+            ("objects".to_string(), format!("{:?}", world.len())),
+            ("animating".to_string(), format!("{animating:?}")),
+        ]
+    }
 }
@@ -1,10 +1,14 @@
 //! The allocated memory for the game state
 
+#[cfg(target_os = "linux")]
 use core::ffi::c_void;
 use core::marker::PhantomData;
 use core::mem::size_of;
+#[cfg(target_os = "linux")]
 use core::sync::atomic::{AtomicBool, Ordering};
 
+use crate::{Macroquad, TextParams, DARKGRAY, GRAY, GREEN, RED, WHITE, YELLOW};
+
 #[cfg(target_os = "linux")]
 extern "C" {
     pub(crate) fn mmap(
@@ -15,50 +19,601 @@ extern "C" {
         fd: i32,
         offset: i64,
     ) -> *mut u8;
+    pub(crate) fn munmap(addr: *const c_void, length: usize) -> i32;
+    pub(crate) fn mprotect(addr: *const c_void, length: usize, prot: i32) -> i32;
+    pub(crate) fn __errno_location() -> *mut i32;
 }
 
+/// Default base address of the game memory
 pub const MEMORY_BASE_ADDR: usize = 0xcdcd_0000;
+
+/// Default number of bytes initially committed for the game memory
 pub const MEMORY_LENGTH: usize = 2 * 1024 * 1024;
 
+/// Default number of bytes of address space reserved for the game memory to grow into
+pub const MEMORY_RESERVE: usize = 64 * 1024 * 1024;
+
+/// Granularity of the memory mapping
+pub const PAGE_SIZE: usize = 0x1000;
+
+/// Default fraction of the game memory that can be used before a warning is emitted
+pub const MEMORY_WARN_THRESHOLD: f32 = 0.9;
+
+/// Tag used for allocations made through [`Memory::alloc`]
+pub const UNTAGGED: &str = "untagged";
+
+/// Tag accounting the allocations of a previous run when resuming a memory file
+pub const RESUMED: &str = "resumed";
+
+/// Number of canary bytes placed before and after each allocation when enabled
+pub const CANARY_SIZE: usize = 0x10;
+
+/// The byte written to every canary
+const CANARY_BYTE: u8 = 0xcd;
+
+/// Identifies a game memory image (`JUICEMEM`)
+const MEMORY_MAGIC: u64 = u64::from_le_bytes(*b"JUICEMEM");
+
+/// Bumped whenever the layout of the [`MemoryHeader`] changes
+const MEMORY_VERSION: u32 = 2;
+
+/// Bookkeeping stored at the start of the game memory itself so that an [`Allocation`]
+/// can validate itself without access to the [`Memory`] and a file backed memory can
+/// be resumed
+#[repr(C)]
+struct MemoryHeader {
+    /// Always [`MEMORY_MAGIC`]
+    magic: u64,
+
+    /// Always [`MEMORY_VERSION`]
+    version: u32,
+
+    /// Mirror of [`Memory::initialized`]
+    initialized: u32,
+
+    /// Base address the memory was mapped at
+    base: u64,
+
+    /// Size of the reserved range
+    reserve: u64,
+
+    /// Mirror of [`Memory::next_allocation`]
+    next_allocation: u64,
+
+    /// Incremented on every [`Memory::reset`] to invalidate all existing allocations
+    generation: u64,
+
+    /// [`MemoryConfig::schema`] of the run that created the memory
+    schema: u64,
+}
+
+/// Offset of the first allocation, leaving room for the [`MemoryHeader`]
+const HEADER_SIZE: usize = (size_of::<MemoryHeader>() + 0xf) & !0xf;
+
+#[cfg(target_os = "linux")]
 static ALLOCATED: AtomicBool = AtomicBool::new(false);
 
 #[cfg(target_os = "linux")]
-pub fn allocate_memory() -> *mut u8 {
-    const PROT_READ: i32 = 0x1;
-    const PROT_WRITE: i32 = 0x2;
+const PROT_NONE: i32 = 0x0;
+#[cfg(target_os = "linux")]
+const PROT_READ: i32 = 0x1;
+#[cfg(target_os = "linux")]
+const PROT_WRITE: i32 = 0x2;
+
+/// Errors that can occur while mapping the game memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryError {
+    /// The configuration given for the memory is invalid
+    InvalidConfig(String),
+
+    /// Something is already mapped in the requested range
+    Collision { base: usize, length: usize },
+
+    /// The kernel mapped the memory somewhere other than the requested base
+    Misplaced { requested: usize, actual: usize },
+
+    /// A memory syscall failed with the given `errno`
+    Syscall { name: &'static str, errno: i32 },
+
+    /// The backing file could not be opened or resized
+    Io(String),
+
+    /// The backing file holds an image that can't be mapped with this config
+    IncompatibleImage(String),
+
+    /// More memory was requested than the reserved range holds
+    OutOfMemory { requested: usize, reserved: usize },
+
+    /// The operation isn't available on this platform
+    Unsupported(&'static str),
+}
+
+impl core::fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MemoryError::InvalidConfig(msg) => write!(f, "Invalid memory config: {msg}"),
+            MemoryError::Collision { base, length } => write!(
+                f,
+                "Game memory {base:#x}..{:#x} collides with an existing mapping. \
+                 Choose another base with --memory-base",
+                base + length
+            ),
+            MemoryError::Misplaced { requested, actual } => write!(
+                f,
+                "Game memory requested at {requested:#x} but mapped at {actual:#x}"
+            ),
+            MemoryError::Syscall { name, errno } => write!(f, "{name} failed: errno {errno}"),
+            MemoryError::Io(msg) => write!(f, "Game memory file: {msg}"),
+            MemoryError::IncompatibleImage(msg) => {
+                write!(f, "Refusing to resume game memory file: {msg}")
+            }
+            MemoryError::OutOfMemory {
+                requested,
+                reserved,
+            } => write!(
+                f,
+                "Requested {requested} bytes of game memory but only {reserved} are reserved"
+            ),
+            MemoryError::Unsupported(what) => write!(f, "{what} is not supported on this platform"),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+/// Where the bytes of the game memory come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryBackend {
+    /// Mapped at the fixed `base` address. Allocations stay at the same address for the
+    /// lifetime of the process, so the game library can be hot reloaded.
+    Fixed,
+
+    /// A heap buffer owned by the [`Memory`] at whatever address the allocator picks.
+    /// The buffer can't grow, so `reserve` is ignored and only `size` bytes are usable.
+    Owned,
+}
+
+/// Where and how large the game memory is mapped
+#[derive(Debug, Clone)]
+pub struct MemoryConfig {
+    /// Where the bytes of the game memory come from
+    pub backend: MemoryBackend,
+
+    /// Base address of the game memory (only used by [`MemoryBackend::Fixed`])
+    pub base: usize,
+
+    /// Number of bytes committed (readable and writable) up front
+    pub size: usize,
+
+    /// Number of bytes of address space reserved for the memory to grow into
+    pub reserve: usize,
+
+    /// Place an inaccessible guard page directly before and after the reserved range
+    pub guard_pages: bool,
+
+    /// Surround each allocation with canary bytes checked by [`Memory::check_canaries`]
+    pub canaries: bool,
+
+    /// Back the memory with this file instead of anonymous memory so it survives a
+    /// restart of the host (only used by [`MemoryBackend::Fixed`])
+    ///
+    /// The file must be new, empty or a game memory image. The allocation records of a
+    /// resumed image are not persisted, so its allocations are only accounted as a
+    /// whole under [`RESUMED`]. They are not checked by [`Memory::check_canaries`] and
+    /// show up as untracked ranges in a [`Memory::diff`].
+    pub file: Option<std::path::PathBuf>,
+
+    /// Version of the layout of the data the game keeps in the memory, supplied by the
+    /// game. A resumed image created with another schema is refused.
+    pub schema: u64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        // Only the owned backend is available without `mmap`
+        let backend = if cfg!(target_os = "linux") {
+            MemoryBackend::Fixed
+        } else {
+            MemoryBackend::Owned
+        };
+
+        Self {
+            backend,
+            base: MEMORY_BASE_ADDR,
+            size: MEMORY_LENGTH,
+            reserve: MEMORY_RESERVE,
+            guard_pages: cfg!(debug_assertions),
+            canaries: false,
+            file: None,
+            schema: 0,
+        }
+    }
+}
+
+impl MemoryConfig {
+    /// Parse the memory config from command line arguments, using the defaults for any
+    /// argument not given
+    ///
+    /// * `--memory-backend <fixed|owned>` - Where the bytes of the memory come from
+    /// * `--memory-base <addr>` - Base address (`0x` prefix for hex)
+    /// * `--memory-size <bytes>` - Initially committed size (`K`, `M`, `G` suffixes allowed)
+    /// * `--memory-reserve <bytes>` - Reserved address space for growth
+    /// * `--memory-guard` / `--no-memory-guard` - Enable or disable the guard pages
+    /// * `--memory-canaries` - Surround each allocation with canaries
+    /// * `--memory-file <path>` - Persist the memory in this file
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, MemoryError> {
+        let mut config = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let field = match arg.as_str() {
+                "--memory-guard" => {
+                    config.guard_pages = true;
+                    continue;
+                }
+                "--no-memory-guard" => {
+                    config.guard_pages = false;
+                    continue;
+                }
+                "--memory-canaries" => {
+                    config.canaries = true;
+                    continue;
+                }
+                "--memory-file" => {
+                    let Some(path) = args.next() else {
+                        return Err(MemoryError::InvalidConfig(format!(
+                            "{arg} requires a value"
+                        )));
+                    };
+
+                    config.file = Some(path.into());
+                    continue;
+                }
+                "--memory-backend" => {
+                    config.backend = match args.next().as_deref() {
+                        Some("fixed") => MemoryBackend::Fixed,
+                        Some("owned") => MemoryBackend::Owned,
+                        value => {
+                            return Err(MemoryError::InvalidConfig(format!(
+                                "--memory-backend must be `fixed` or `owned`, got {value:?}"
+                            )))
+                        }
+                    };
+                    continue;
+                }
+                "--memory-base" => &mut config.base,
+                "--memory-size" => &mut config.size,
+                "--memory-reserve" => &mut config.reserve,
+                _ => continue,
+            };
+
+            let Some(value) = args.next() else {
+                return Err(MemoryError::InvalidConfig(format!(
+                    "{arg} requires a value"
+                )));
+            };
+
+            *field = parse_size(&value)
+                .ok_or_else(|| MemoryError::InvalidConfig(format!("Invalid {arg}: {value}")))?;
+        }
+
+        // Growing up to the committed size is always allowed
+        config.reserve = config.reserve.max(config.size);
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Ensure the config can be mapped
+    pub fn validate(&self) -> Result<(), MemoryError> {
+        if self.backend == MemoryBackend::Fixed && !cfg!(target_os = "linux") {
+            return Err(MemoryError::InvalidConfig(
+                "The fixed memory backend is only available on Linux".to_string(),
+            ));
+        }
+
+        let fixed = self.backend == MemoryBackend::Fixed;
+
+        if !fixed && self.file.is_some() {
+            return Err(MemoryError::InvalidConfig(
+                "A memory file requires the fixed memory backend".to_string(),
+            ));
+        }
+
+        if fixed && (self.base == 0 || self.base % PAGE_SIZE != 0) {
+            return Err(MemoryError::InvalidConfig(format!(
+                "Base {:#x} must be a non-null multiple of {PAGE_SIZE:#x}",
+                self.base
+            )));
+        }
+
+        if self.size == 0 || self.size % PAGE_SIZE != 0 || self.reserve % PAGE_SIZE != 0 {
+            return Err(MemoryError::InvalidConfig(format!(
+                "Size {:#x} and reserve {:#x} must be non-zero multiples of {PAGE_SIZE:#x}",
+                self.size, self.reserve
+            )));
+        }
+
+        if fixed && self.guard_pages && self.base < PAGE_SIZE * 2 {
+            return Err(MemoryError::InvalidConfig(format!(
+                "Base {:#x} leaves no room for the leading guard page",
+                self.base
+            )));
+        }
+
+        if self.reserve < self.size {
+            return Err(MemoryError::InvalidConfig(format!(
+                "Reserve {:#x} is smaller than size {:#x}",
+                self.reserve, self.size
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a number of bytes or an address such as `0xcdcd0000`, `4096`, `2M` or `1G`
+fn parse_size(value: &str) -> Option<usize> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        return usize::from_str_radix(&hex.replace('_', ""), 16).ok();
+    }
+
+    let (number, shift) = match value.as_bytes().last()? {
+        b'k' | b'K' => (&value[..value.len() - 1], 10),
+        b'm' | b'M' => (&value[..value.len() - 1], 20),
+        b'g' | b'G' => (&value[..value.len() - 1], 30),
+        _ => (value, 0),
+    };
+
+    number.parse::<usize>().ok()?.checked_mul(1 << shift)
+}
+
+/// Get the `errno` of the last failed syscall
+#[cfg(target_os = "linux")]
+fn errno() -> i32 {
+    unsafe { *__errno_location() }
+}
+
+/// Reserve the address space for the game memory and commit the initial size
+#[cfg(target_os = "linux")]
+pub fn allocate_memory(config: &MemoryConfig) -> Result<*mut u8, MemoryError> {
+    const MAP_SHARED: i32 = 0x01;
     const MAP_PRIVATE: i32 = 0x02;
-    const MAP_ANON: i32 = 0x20;
     const MAP_FIXED: i32 = 0x10;
+    const MAP_ANON: i32 = 0x20;
+    const MAP_NORESERVE: i32 = 0x4000;
+    const MAP_FIXED_NOREPLACE: i32 = 0x10_0000;
     const MAP_FAILED: isize = -1_isize;
+    const EEXIST: i32 = 17;
+
+    config.validate()?;
 
     assert!(
         !ALLOCATED.load(Ordering::SeqCst),
         "Attempted to allocate game memory twice"
     );
 
-    // Allocate the memory at the requested base address
+    // The guard pages are part of the reservation and are never committed
+    let guard = if config.guard_pages { PAGE_SIZE } else { 0 };
+    let map_base = config.base - guard;
+    let map_length = config.reserve + guard * 2;
+
+    // Reserve the full range without access. Unlike `MAP_FIXED`, `MAP_FIXED_NOREPLACE`
+    // refuses to clobber anything already mapped in this range
     let res = unsafe {
         mmap(
-            MEMORY_BASE_ADDR as *const c_void,
-            MEMORY_LENGTH,
-            PROT_READ | PROT_WRITE,
-            MAP_PRIVATE | MAP_ANON | MAP_FIXED,
+            map_base as *const c_void,
+            map_length,
+            PROT_NONE,
+            MAP_PRIVATE | MAP_ANON | MAP_NORESERVE | MAP_FIXED_NOREPLACE,
             -1,
             0,
         )
     };
 
-    assert!(res != MAP_FAILED as *mut u8);
+    if res == MAP_FAILED as *mut u8 {
+        return Err(match errno() {
+            EEXIST => MemoryError::Collision {
+                base: map_base,
+                length: map_length,
+            },
+            errno => MemoryError::Syscall {
+                name: "mmap",
+                errno,
+            },
+        });
+    }
+
+    // Kernels older than 4.17 treat `MAP_FIXED_NOREPLACE` as a hint
+    if res as usize != map_base {
+        unsafe {
+            munmap(res as *const c_void, map_length);
+        }
+
+        return Err(MemoryError::Misplaced {
+            requested: map_base,
+            actual: res as usize,
+        });
+    }
+
+    // Release the whole reservation if anything below fails
+    let unmap = |err| {
+        unsafe {
+            munmap(map_base as *const c_void, map_length);
+        }
+
+        err
+    };
+
+    // Is an existing image in the backing file being resumed
+    let mut resume = false;
+
+    // Replace the reservation (not the guard pages) with the backing file. `MAP_FIXED`
+    // is safe here since the range was just reserved by us.
+    if let Some(path) = &config.file {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .and_then(|file| Ok((file.metadata()?.len(), file)))
+            .map_err(|err| unmap(MemoryError::Io(format!("{}: {err}", path.display()))))?;
+
+        let (len, file) = file;
+
+        // A new file is sized to the reserve. An existing image must already match it.
+        if len == 0 {
+            file.set_len(config.reserve as u64)
+                .map_err(|err| unmap(MemoryError::Io(format!("{}: {err}", path.display()))))?;
+        } else if len != config.reserve as u64 {
+            return Err(unmap(MemoryError::IncompatibleImage(format!(
+                "{} is {len:#x} bytes but the reserve is {:#x}",
+                path.display(),
+                config.reserve
+            ))));
+        }
+
+        resume = len != 0;
+
+        let res = unsafe {
+            mmap(
+                config.base as *const c_void,
+                config.reserve,
+                PROT_NONE,
+                MAP_SHARED | MAP_FIXED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if res == MAP_FAILED as *mut u8 {
+            return Err(unmap(MemoryError::Syscall {
+                name: "mmap",
+                errno: errno(),
+            }));
+        }
+    }
+
+    // Commit the initial size
+    commit_memory(config.base, config.size).map_err(unmap)?;
+
+    // Refuse to resume anything but an image created with the same layout, so an
+    // unrelated file is never overwritten
+    let header = unsafe { &*(config.base as *const MemoryHeader) };
+    if resume {
+        validate_header(header, config).map_err(unmap)?;
+
+        // Commit everything the previous run allocated
+        let needed = (header.next_allocation as usize).next_multiple_of(PAGE_SIZE);
+        if needed > config.size {
+            commit_memory(config.base, needed).map_err(unmap)?;
+        }
+    }
 
     // Globally signal that the game memory has been allocated
     ALLOCATED.store(true, Ordering::SeqCst);
 
-    res
+    Ok(config.base as *mut u8)
+}
+
+/// Ensure a resumed `header` matches the layout requested by `config`
+#[cfg(target_os = "linux")]
+fn validate_header(header: &MemoryHeader, config: &MemoryConfig) -> Result<(), MemoryError> {
+    if header.magic != MEMORY_MAGIC {
+        return Err(MemoryError::IncompatibleImage(format!(
+            "Bad magic {:#x}",
+            header.magic
+        )));
+    }
+
+    if header.version != MEMORY_VERSION {
+        return Err(MemoryError::IncompatibleImage(format!(
+            "Image version {} but expected {MEMORY_VERSION}",
+            header.version
+        )));
+    }
+
+    if header.schema != config.schema {
+        return Err(MemoryError::IncompatibleImage(format!(
+            "Image schema {} but the game expects {}",
+            header.schema, config.schema
+        )));
+    }
+
+    if header.base as usize != config.base || header.reserve as usize != config.reserve {
+        return Err(MemoryError::IncompatibleImage(format!(
+            "Image was mapped at {:#x} with reserve {:#x} but requested {:#x} with reserve {:#x}",
+            header.base, header.reserve, config.base, config.reserve
+        )));
+    }
+
+    if header.next_allocation as usize > config.reserve {
+        return Err(MemoryError::IncompatibleImage(format!(
+            "Next allocation {:#x} is outside of the reserve",
+            header.next_allocation
+        )));
+    }
+
+    Ok(())
+}
+
+/// Make `length` bytes at `addr` readable and writable
+#[cfg(target_os = "linux")]
+fn commit_memory(addr: usize, length: usize) -> Result<(), MemoryError> {
+    let res = unsafe { mprotect(addr as *const c_void, length, PROT_READ | PROT_WRITE) };
+
+    if res != 0 {
+        return Err(MemoryError::Syscall {
+            name: "mprotect",
+            errno: errno(),
+        });
+    }
+
+    Ok(())
 }
 
 // #[cfg(not(target_os = "linux"))]
 // compile_error!("Memory allocation not written for this operating system");
 
+/// A heap buffer backing a [`MemoryBackend::Owned`] memory
+struct OwnedBuffer {
+    /// Start of the buffer
+    ptr: *mut u8,
+
+    /// The layout the buffer was allocated with
+    layout: std::alloc::Layout,
+}
+
+impl OwnedBuffer {
+    /// Allocate a zeroed, page aligned buffer of `size` bytes
+    fn new(size: usize) -> Result<Self, MemoryError> {
+        let layout = std::alloc::Layout::from_size_align(size, PAGE_SIZE)
+            .map_err(|err| MemoryError::InvalidConfig(err.to_string()))?;
+
+        // SAFETY: `validate` ensures the size is non-zero
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+
+        if ptr.is_null() {
+            return Err(MemoryError::InvalidConfig(format!(
+                "Failed to allocate {size:#x} bytes for the game memory"
+            )));
+        }
+
+        Ok(Self { ptr, layout })
+    }
+}
+
+impl Drop for OwnedBuffer {
+    fn drop(&mut self) {
+        // SAFETY: The buffer was allocated with this layout in `new`
+        unsafe { std::alloc::dealloc(self.ptr, self.layout) }
+    }
+}
+
 /// Memory chunk allocated for the game with a basic bump allocator
 pub struct Memory {
     /// Has this memory been initialized by the game yet
@@ -66,65 +621,361 @@ pub struct Memory {
 
     /// Offset to the next allocation in the memory region
     pub next_allocation: usize,
+
+    /// Base address of the memory region
+    base: usize,
+
+    /// The buffer backing the memory if it is not mapped at a fixed address, kept to
+    /// free it on drop
+    #[allow(dead_code)]
+    owned: Option<OwnedBuffer>,
+
+    /// Number of bytes currently readable and writable
+    committed: usize,
+
+    /// Number of bytes of address space the memory can grow into
+    reserved: usize,
+
+    /// Are allocations surrounded by canaries
+    canaries: bool,
+
+    /// Every allocation made in this memory
+    allocations: Vec<AllocationRecord>,
+
+    /// Usage statistics for each allocation tag
+    tags: Vec<TagStats>,
+
+    /// The highest `next_allocation` seen for this memory
+    high_water: usize,
+
+    /// Fraction of the memory that can be used before emitting a warning
+    warn_threshold: f32,
+
+    /// Has the usage warning already been emitted
+    warned: bool,
+
+    /// The usage warning not yet taken by [`Memory::take_usage_warning`]
+    warning: Option<UsageWarning>,
+}
+
+/// Bookkeeping for a single allocation in the game memory
+#[derive(Debug, Clone)]
+pub struct AllocationRecord {
+    /// Offset of the allocated value from the memory base
+    pub offset: usize,
+
+    /// Size of the allocated value (excluding canaries and padding)
+    pub size: usize,
+
+    /// The tag given to the allocation
+    pub tag: String,
+
+    /// The name of the allocated type
+    pub type_name: String,
+}
+
+/// A corrupted canary found by [`Memory::check_canaries`]
+#[derive(Debug, Clone)]
+pub struct Corruption {
+    /// The tag of the corrupted allocation
+    pub tag: String,
+
+    /// The name of the corrupted allocation's type
+    pub type_name: String,
+
+    /// Offset of the corrupted allocation from the memory base
+    pub allocation: usize,
+
+    /// Size of the corrupted allocation
+    pub size: usize,
+
+    /// Offset of the first corrupted byte from the memory base
+    pub offset: usize,
+
+    /// Was the write past the end (`true`) or before the start (`false`) of the value
+    pub overflow: bool,
+}
+
+impl core::fmt::Display for Corruption {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (direction, distance) = if self.overflow {
+            (
+                "past the end of",
+                self.offset - (self.allocation + self.size) + 1,
+            )
+        } else {
+            ("before", self.allocation - self.offset)
+        };
+
+        write!(
+            f,
+            "'{}' ({}) at {:#x} corrupted at {:#x}, {distance} bytes {direction} the allocation",
+            self.tag, self.type_name, self.allocation, self.offset
+        )
+    }
+}
+
+/// Emitted once by [`Memory::take_usage_warning`] when the memory usage crosses the
+/// warning threshold
+#[derive(Debug, Clone)]
+pub struct UsageWarning {
+    /// Bytes used when the threshold was crossed
+    pub used: usize,
+
+    /// Maximum number of bytes the game memory can grow to
+    pub capacity: usize,
+
+    /// The usage of each tag when the threshold was crossed
+    pub tags: Vec<TagStats>,
+}
+
+impl core::fmt::Display for UsageWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Game memory {:.1}% used ({} / {} bytes)",
+            self.used as f32 / self.capacity as f32 * 100.,
+            self.used,
+            self.capacity
+        )?;
+
+        for stats in &self.tags {
+            write!(f, "\n  {:>16}: {} bytes", stats.tag, stats.current)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Memory usage for all allocations sharing a tag
+#[derive(Debug, Clone, Default)]
+pub struct TagStats {
+    /// The tag given to the allocations
+    ///
+    /// This is owned since a `&'static str` from the game library would dangle after
+    /// a hot reload
+    pub tag: String,
+
+    /// Number of allocations made with this tag
+    pub allocations: usize,
+
+    /// Bytes currently used by this tag (including alignment padding)
+    pub current: usize,
+
+    /// The most bytes ever used by this tag
+    pub high_water: usize,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Debug)]
 pub struct Allocation<T> {
+    /// Base address of the memory this allocation lives in
+    base: usize,
+
     /// Index into the memory for this allocation
     index: usize,
 
+    /// The memory generation this allocation was made in
+    generation: u64,
+
     /// The type of this allocation
     phantom: PhantomData<T>,
 }
 
+// Implemented by hand since deriving would require `T: Copy` for what is only a handle
+impl<T> Clone for Allocation<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Allocation<T> {}
+
 impl<T> core::default::Default for Allocation<T> {
     fn default() -> Self {
         Self {
+            base: 0,
             index: !0,
+            generation: 0,
             phantom: PhantomData,
         }
     }
 }
 
+impl<T> Allocation<T> {
+    /// Returns `true` if this allocation was made by `alloc` in the current generation
+    /// of its memory
+    pub fn is_valid(&self) -> bool {
+        if self.base == 0 {
+            return false;
+        }
+
+        // SAFETY: A non-zero base is only set by `alloc`, where the header is mapped
+        let header = unsafe { &*(self.base as *const MemoryHeader) };
+
+        header.generation == self.generation
+    }
+
+    /// Get a reference to the allocated value, or `None` if this allocation is the
+    /// default or from before a [`Memory::reset`]
+    pub fn get(&self) -> Option<&T> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        // SAFETY: The allocation is valid and `alloc` checked the index was in bounds
+        Some(unsafe { &*((self.base + self.index) as *const T) })
+    }
+
+    /// Get a mutable reference to the allocated value, or `None` if this allocation is
+    /// the default or from before a [`Memory::reset`]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        // SAFETY: The allocation is valid and `alloc` checked the index was in bounds
+        Some(unsafe { &mut *((self.base + self.index) as *mut T) })
+    }
+
+    /// Get a raw pointer to the allocated value, or `None` if this allocation is the
+    /// default or from before a [`Memory::reset`]
+    ///
+    /// Unlike [`Allocation::get_mut`] this doesn't assume the value is initialized, so
+    /// it can be used to write a value field by field.
+    pub fn as_mut_ptr(&mut self) -> Option<*mut T> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some((self.base + self.index) as *mut T)
+    }
+
+    /// Panic with the reason this allocation can't be dereferenced
+    #[cold]
+    #[track_caller]
+    fn invalid(&self) -> ! {
+        if self.base == 0 {
+            panic!(
+                "Dereferenced a default Allocation<{}>",
+                core::any::type_name::<T>()
+            );
+        }
+
+        panic!(
+            "Dereferenced a stale Allocation<{}> from generation {}",
+            core::any::type_name::<T>(),
+            self.generation
+        );
+    }
+}
+
 impl<T> core::ops::Deref for Allocation<T> {
     type Target = T;
 
+    #[track_caller]
     fn deref(&self) -> &Self::Target {
+        #[cfg(debug_assertions)]
+        if !self.is_valid() {
+            self.invalid();
+        }
+
         // SAFETY: The only way to create an Allocation is through `alloc` which checks
         //         that the index was in bounds
-        unsafe { &*((MEMORY_BASE_ADDR + self.index) as *const T) }
+        unsafe { &*((self.base + self.index) as *const T) }
     }
 }
 
 impl<T> core::ops::DerefMut for Allocation<T> {
+    #[track_caller]
     fn deref_mut(&mut self) -> &mut Self::Target {
+        #[cfg(debug_assertions)]
+        if !self.is_valid() {
+            self.invalid();
+        }
+
         // SAFETY: The only way to create an Allocation is through `alloc` which checks
         //         that the index was in bounds
-        unsafe { &mut *((MEMORY_BASE_ADDR + self.index) as *mut T) }
+        unsafe { &mut *((self.base + self.index) as *mut T) }
     }
 }
 
 impl Memory {
-    /// Allocate a new chunk of memory
-    #[cfg(target_os = "linux")]
+    /// Allocate a new chunk of memory using the default [`MemoryConfig`]
+    ///
+    /// # Panics
+    ///
+    /// * The memory could not be mapped
     pub fn new() -> Self {
+        Self::with_config(&MemoryConfig::default()).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Allocate a new chunk of memory as described by `config`
+    pub fn with_config(config: &MemoryConfig) -> Result<Self, MemoryError> {
+        config.validate()?;
+
         // Allocate the memory for the game
-        allocate_memory();
+        let (base, reserved, owned) = match config.backend {
+            #[cfg(target_os = "linux")]
+            MemoryBackend::Fixed => {
+                allocate_memory(config)?;
+                (config.base, config.reserve, None)
+            }
+            #[cfg(not(target_os = "linux"))]
+            MemoryBackend::Fixed => unreachable!("Checked by validate"),
+            MemoryBackend::Owned => {
+                let buffer = OwnedBuffer::new(config.size)?;
+                (buffer.ptr as usize, config.size, Some(buffer))
+            }
+        };
 
-        //
-        Self {
-            initialized: false,
-            next_allocation: 0,
+        // A mapped file with a valid header (checked in `allocate_memory`) is resumed
+        // instead of reinitialized. Anything else was just created and is all zeros.
+        let header = unsafe { &mut *(base as *mut MemoryHeader) };
+        let resumed = header.magic == MEMORY_MAGIC;
+        if !resumed {
+            // Start at generation 1 so default allocations are never valid
+            *header = MemoryHeader {
+                magic: MEMORY_MAGIC,
+                version: MEMORY_VERSION,
+                initialized: 0,
+                base: base as u64,
+                reserve: reserved as u64,
+                next_allocation: HEADER_SIZE as u64,
+                generation: 1,
+                schema: config.schema,
+            };
         }
-    }
 
-    /// Allocate a new chunk of memory
-    #[cfg(target_family = "wasm")]
-    pub fn new() -> Self {
-        Self {
-            initialized: false,
-            next_allocation: 0,
+        let next_allocation = header.next_allocation as usize;
+        let committed = config.size.max(next_allocation.next_multiple_of(PAGE_SIZE));
+
+        // The records of resumed allocations are lost, so account them as a whole
+        let mut tags = Vec::new();
+        if resumed && next_allocation > HEADER_SIZE {
+            tags.push(TagStats {
+                tag: RESUMED.to_string(),
+                allocations: 1,
+                current: next_allocation - HEADER_SIZE,
+                high_water: next_allocation - HEADER_SIZE,
+            });
         }
+
+        //
+        Ok(Self {
+            initialized: header.initialized != 0,
+            next_allocation,
+            base,
+            owned,
+            committed,
+            reserved,
+            canaries: config.canaries,
+            allocations: Vec::new(),
+            tags,
+            high_water: next_allocation,
+            warn_threshold: MEMORY_WARN_THRESHOLD,
+            warned: false,
+            warning: None,
+        })
     }
 
     /// Allocate `T` in the allocated game memory
@@ -133,31 +984,705 @@ impl Memory {
     ///
     /// * Out of allocated memory
     pub fn alloc<T: Sized>(&mut self) -> Allocation<T> {
+        self.alloc_tagged(UNTAGGED)
+    }
+
+    /// Allocate `T` in the allocated game memory, accounting the usage to `tag`
+    ///
+    /// # Panics
+    ///
+    /// * Out of allocated memory
+    /// * The pages for the allocation could not be committed
+    pub fn alloc_tagged<T: Sized>(&mut self, tag: &str) -> Allocation<T> {
         let size = size_of::<T>();
+        let canary = if self.canaries { CANARY_SIZE } else { 0 };
 
         // SAFETY: This is the main safety check to ensure all allocations are in bounds
         assert!(
-            self.next_allocation + size < MEMORY_LENGTH,
+            self.next_allocation + canary + size + canary < self.reserved,
             "Out of game memory"
         );
 
+        // Commit more of the reserved range if this allocation doesn't fit
+        self.grow(self.next_allocation + canary + size + canary)
+            .unwrap_or_else(|err| panic!("Failed to grow game memory: {err}"));
+
+        let start = self.next_allocation;
+
         // Get the index for this allocation
-        let index = self.next_allocation;
+        let index = start + canary;
 
         // Bump the allocation to fit the requested type
-        self.next_allocation += size;
+        self.next_allocation = index + size;
+
+        // Surround the allocation with canaries
+        if self.canaries {
+            unsafe {
+                let base = self.base as *mut u8;
+                core::ptr::write_bytes(base.add(start), CANARY_BYTE, CANARY_SIZE);
+                core::ptr::write_bytes(base.add(index + size), CANARY_BYTE, CANARY_SIZE);
+            }
+
+            self.next_allocation += CANARY_SIZE;
+        }
 
         // 64 bit align the next allocation
         self.next_allocation = (self.next_allocation + 0xf) & !0xf;
 
+        // Account this allocation to its tag
+        self.track(tag, self.next_allocation - start);
+        self.sync_header();
+
+        self.allocations.push(AllocationRecord {
+            offset: index,
+            size,
+            tag: tag.to_string(),
+            type_name: core::any::type_name::<T>().to_string(),
+        });
+
         Allocation {
+            base: self.base,
             index,
+            generation: self.generation(),
             phantom: PhantomData,
         }
     }
 
+    /// Ensure at least `length` bytes are committed, doubling the committed size to
+    /// amortize the syscalls
+    fn grow(&mut self, length: usize) -> Result<(), MemoryError> {
+        if length <= self.committed {
+            return Ok(());
+        }
+
+        if length > self.reserved {
+            return Err(MemoryError::OutOfMemory {
+                requested: length,
+                reserved: self.reserved,
+            });
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let new_committed = (self.committed * 2)
+                .max(length.next_multiple_of(PAGE_SIZE))
+                .min(self.reserved);
+
+            commit_memory(self.base + self.committed, new_committed - self.committed)?;
+            self.committed = new_committed;
+            Ok(())
+        }
+
+        // Only the fixed mapping commits lazily, the owned buffer is committed up front
+        #[cfg(not(target_os = "linux"))]
+        Err(MemoryError::Unsupported("Growing game memory"))
+    }
+
+    /// Add `size` bytes to the usage of `tag` and check the warning threshold
+    fn track(&mut self, tag: &str, size: usize) {
+        let stats = match self.tags.iter().position(|stats| stats.tag == tag) {
+            Some(index) => &mut self.tags[index],
+            None => {
+                self.tags.push(TagStats {
+                    tag: tag.to_string(),
+                    ..Default::default()
+                });
+                self.tags.last_mut().unwrap()
+            }
+        };
+
+        stats.allocations += 1;
+        stats.current += size;
+        stats.high_water = stats.high_water.max(stats.current);
+
+        self.high_water = self.high_water.max(self.next_allocation);
+
+        // Warn once when crossing the threshold, well before the hard out of memory panic
+        let used = self.next_allocation as f32 / self.reserved as f32;
+        if !self.warned && used >= self.warn_threshold {
+            self.warning = Some(UsageWarning {
+                used: self.next_allocation,
+                capacity: self.reserved,
+                tags: self.tags.clone(),
+            });
+            self.warned = true;
+        }
+    }
+
+    /// Get the header at the start of the memory
+    fn header(&self) -> &MemoryHeader {
+        // SAFETY: The header is written when the memory is mapped
+        unsafe { &*(self.base as *const MemoryHeader) }
+    }
+
+    /// Get a mutable reference to the header at the start of the memory
+    fn header_mut(&mut self) -> &mut MemoryHeader {
+        // SAFETY: The header is written when the memory is mapped
+        unsafe { &mut *(self.base as *mut MemoryHeader) }
+    }
+
+    /// The current generation of this memory. Only allocations from this generation
+    /// are valid.
+    pub fn generation(&self) -> u64 {
+        self.header().generation
+    }
+
+    /// Free every allocation, invalidating all existing [`Allocation`]s
+    ///
+    /// Committed pages stay committed and the high water marks are kept
+    pub fn reset(&mut self) {
+        self.header_mut().generation += 1;
+        self.next_allocation = HEADER_SIZE;
+        self.initialized = false;
+        self.allocations.clear();
+        self.warned = false;
+        self.warning = None;
+
+        for stats in &mut self.tags {
+            stats.allocations = 0;
+            stats.current = 0;
+        }
+
+        self.sync_header();
+    }
+
+    /// Mirror `initialized` and `next_allocation` into the header at the start of the
+    /// memory so a file backed memory resumes with them
+    pub fn sync_header(&mut self) {
+        let initialized = self.initialized as u32;
+        let next_allocation = self.next_allocation as u64;
+
+        let header = self.header_mut();
+        header.initialized = initialized;
+        header.next_allocation = next_allocation;
+    }
+
+    /// Check the canaries around every allocation, returning the first corrupted byte
+    /// of each corrupted canary
+    ///
+    /// Corrupted canaries are rewritten so each corruption is only reported once
+    pub fn check_canaries(&mut self) -> Vec<Corruption> {
+        let mut corruptions = Vec::new();
+
+        if !self.canaries {
+            return corruptions;
+        }
+
+        for record in &self.allocations {
+            let before = record.offset - CANARY_SIZE;
+            let after = record.offset + record.size;
+
+            for (start, overflow) in [(before, false), (after, true)] {
+                // SAFETY: Canaries were written in the committed memory during `alloc`
+                let canary = unsafe {
+                    core::slice::from_raw_parts_mut((self.base + start) as *mut u8, CANARY_SIZE)
+                };
+
+                // Report the corrupted byte closest to the allocation itself
+                let corrupted = if overflow {
+                    canary.iter().position(|byte| *byte != CANARY_BYTE)
+                } else {
+                    canary.iter().rposition(|byte| *byte != CANARY_BYTE)
+                };
+
+                let Some(corrupted) = corrupted else {
+                    continue;
+                };
+
+                corruptions.push(Corruption {
+                    tag: record.tag.clone(),
+                    type_name: record.type_name.clone(),
+                    allocation: record.offset,
+                    size: record.size,
+                    offset: start + corrupted,
+                    overflow,
+                });
+
+                canary.fill(CANARY_BYTE);
+            }
+        }
+
+        corruptions
+    }
+
+    /// Get the bookkeeping for every allocation made in this memory
+    pub fn allocations(&self) -> &[AllocationRecord] {
+        &self.allocations
+    }
+
+    /// Take the warning emitted when the usage crossed the warning threshold, if it
+    /// hasn't been taken yet
+    pub fn take_usage_warning(&mut self) -> Option<UsageWarning> {
+        self.warning.take()
+    }
+
+    /// Set the fraction (`0.0..=1.0`) of memory used before a warning is emitted
+    pub fn set_warn_threshold(&mut self, threshold: f32) {
+        self.warn_threshold = threshold.clamp(0.0, 1.0);
+        self.warned = false;
+        self.warning = None;
+    }
+
+    /// Get the usage statistics for each allocation tag
+    pub fn tag_stats(&self) -> &[TagStats] {
+        &self.tags
+    }
+
+    /// Number of bytes currently used in the game memory
+    pub fn used(&self) -> usize {
+        self.next_allocation
+    }
+
+    /// The most bytes ever used in the game memory
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Base address of the game memory
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Number of bytes currently readable and writable
+    pub fn committed(&self) -> usize {
+        self.committed
+    }
+
+    /// Maximum number of bytes the game memory can grow to
+    pub fn capacity(&self) -> usize {
+        self.reserved
+    }
+
+    /// Draw the current memory usage in the top left of the screen
+    pub fn draw_overlay(&self, macroquad: &Macroquad) {
+        const X: f32 = 0.5;
+        const WIDTH: f32 = 8.0;
+        const LINE_HEIGHT: f32 = 0.6;
+
+        let (font_size, font_scale, font_aspect) = (macroquad.camera_font_scale)(0.5);
+        let text_params = |color| TextParams {
+            font_size,
+            font_scale,
+            font_scale_aspect: font_aspect,
+            color,
+            ..Default::default()
+        };
+
+        // Background bar with the current usage and the high water mark
+        let used = self.used() as f32 / self.capacity() as f32;
+        let committed = self.committed() as f32 / self.capacity() as f32;
+        let high_water = self.high_water() as f32 / self.capacity() as f32;
+        let bar_color = if used >= self.warn_threshold {
+            RED
+        } else {
+            GREEN
+        };
+
+        (macroquad.draw_rectangle)(X, 0.5, WIDTH, 0.3, DARKGRAY);
+        (macroquad.draw_rectangle)(X, 0.5, WIDTH * committed, 0.3, GRAY);
+        (macroquad.draw_rectangle)(X, 0.5, WIDTH * used, 0.3, bar_color);
+        (macroquad.draw_rectangle)(X + WIDTH * high_water, 0.5, 0.05, 0.3, YELLOW);
+
+        let mut y = 1.4;
+        (macroquad.draw_text_ex)(
+            &format!(
+                "memory: {} / {} bytes (high {}, committed {})",
+                self.used(),
+                self.capacity(),
+                self.high_water(),
+                self.committed()
+            ),
+            X,
+            y,
+            text_params(WHITE),
+        );
+
+        // Usage per tag
+        for stats in &self.tags {
+            y += LINE_HEIGHT;
+            (macroquad.draw_text_ex)(
+                &format!(
+                    "{}: {} bytes (high {}, {} allocs)",
+                    stats.tag, stats.current, stats.high_water, stats.allocations
+                ),
+                X,
+                y,
+                text_params(WHITE),
+            );
+        }
+    }
+
     /// Create a copy of the current data as a Vec<u8>
     pub fn data_as_vec(&self) -> Vec<u8> {
-        unsafe { std::slice::from_raw_parts(MEMORY_BASE_ADDR as *const u8, MEMORY_LENGTH).to_vec() }
+        unsafe { std::slice::from_raw_parts(self.base as *const u8, self.committed).to_vec() }
+    }
+
+    /// Overwrite the start of the memory with `data` from [`Memory::data_as_vec`]
+    ///
+    /// # Panics
+    ///
+    /// * `data` doesn't fit in the reserved memory or could not be committed
+    pub fn restore(&mut self, data: &[u8]) {
+        self.grow(data.len())
+            .unwrap_or_else(|err| panic!("Failed to restore game memory: {err}"));
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), self.base as *mut u8, data.len());
+        }
+
+        // Continue allocating from where the snapshot left off
+        let (initialized, next_allocation) = {
+            let header = self.header();
+            (header.initialized != 0, header.next_allocation as usize)
+        };
+
+        self.initialized = initialized;
+        self.next_allocation = next_allocation;
+
+        // Forget the allocations made after the snapshot, their bytes were overwritten
+        self.allocations
+            .retain(|record| record.offset + record.size <= next_allocation);
+
+        // Recount the tag usage from the remaining allocations
+        let canary = if self.canaries { CANARY_SIZE } else { 0 };
+        for stats in &mut self.tags {
+            stats.allocations = 0;
+            stats.current = 0;
+        }
+
+        for record in &self.allocations {
+            let start = record.offset - canary;
+            let end = (record.offset + record.size + canary + 0xf) & !0xf;
+
+            if let Some(stats) = self.tags.iter_mut().find(|stats| stats.tag == record.tag) {
+                stats.allocations += 1;
+                stats.current += end - start;
+            }
+        }
+
+        self.sync_header();
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A small memory on the owned backend, usable by any number of tests at once
+    pub(crate) fn owned_memory(canaries: bool) -> Memory {
+        Memory::with_config(&MemoryConfig {
+            backend: MemoryBackend::Owned,
+            size: 16 * PAGE_SIZE,
+            reserve: 16 * PAGE_SIZE,
+            canaries,
+            ..Default::default()
+        })
+        .expect("Owned memory is always available")
+    }
+
+    #[test]
+    fn growing_past_the_reservation_is_an_error() {
+        let mut memory = owned_memory(false);
+
+        assert_eq!(memory.grow(4 * PAGE_SIZE), Ok(()));
+        assert_eq!(
+            memory.grow(17 * PAGE_SIZE),
+            Err(MemoryError::OutOfMemory {
+                requested: 17 * PAGE_SIZE,
+                reserved: 16 * PAGE_SIZE,
+            })
+        );
+    }
+
+    #[test]
+    fn allocations_are_valid_until_reset() {
+        let mut memory = owned_memory(false);
+        let mut value = memory.alloc::<u64>();
+
+        assert!(value.is_valid());
+        *value.get_mut().unwrap() = 5;
+        assert_eq!(value.get(), Some(&5));
+
+        memory.reset();
+
+        assert!(!value.is_valid());
+        assert_eq!(value.get(), None);
+        assert_eq!(value.get_mut(), None);
+
+        // New allocations in the next generation are valid again
+        let value = memory.alloc::<u64>();
+        assert!(value.is_valid());
+    }
+
+    #[test]
+    fn default_allocation_is_invalid() {
+        let value = Allocation::<u64>::default();
+
+        assert!(!value.is_valid());
+        assert_eq!(value.get(), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "stale Allocation")]
+    fn stale_deref_panics() {
+        let mut memory = owned_memory(false);
+        let value = memory.alloc::<u64>();
+        memory.reset();
+
+        let _ = *value;
+    }
+
+    #[test]
+    fn restore_forgets_later_allocations() {
+        let mut memory = owned_memory(true);
+        let _kept = memory.alloc_tagged::<u64>("kept");
+        let snapshot = memory.data_as_vec();
+        let used = memory.used();
+        let kept_stats = memory.tag_stats()[0].clone();
+
+        let _dropped = memory.alloc_tagged::<[u8; 100]>("dropped");
+        let _also_kept = memory.alloc_tagged::<u32>("kept");
+        memory.restore(&snapshot);
+
+        assert_eq!(memory.used(), used);
+        assert_eq!(memory.allocations().len(), 1);
+        assert_eq!(memory.allocations()[0].tag, "kept");
+
+        let stats = memory.tag_stats();
+        let kept = stats.iter().find(|stats| stats.tag == "kept").unwrap();
+        let dropped = stats.iter().find(|stats| stats.tag == "dropped").unwrap();
+        assert_eq!(
+            (kept.allocations, kept.current),
+            (kept_stats.allocations, kept_stats.current)
+        );
+        assert_eq!((dropped.allocations, dropped.current), (0, 0));
+
+        // The canaries of the forgotten allocations were overwritten by the snapshot
+        assert!(memory.check_canaries().is_empty());
+    }
+
+    /// Get the stats of `tag`
+    fn stats<'a>(memory: &'a Memory, tag: &str) -> &'a TagStats {
+        memory
+            .tag_stats()
+            .iter()
+            .find(|stats| stats.tag == tag)
+            .expect("Tag was used")
+    }
+
+    #[test]
+    fn tag_stats_count_each_tag() {
+        let mut memory = owned_memory(false);
+        memory.alloc_tagged::<u64>("blocks");
+        memory.alloc_tagged::<[u8; 20]>("blocks");
+        memory.alloc::<u32>();
+
+        // Each allocation is padded to 16 bytes
+        let blocks = stats(&memory, "blocks");
+        assert_eq!(blocks.allocations, 2);
+        assert_eq!(blocks.current, 16 + 32);
+        assert_eq!(blocks.high_water, 16 + 32);
+
+        let untagged = stats(&memory, UNTAGGED);
+        assert_eq!(untagged.allocations, 1);
+        assert_eq!(untagged.current, 16);
+
+        assert_eq!(memory.used(), HEADER_SIZE + 64);
+    }
+
+    #[test]
+    fn high_water_survives_reset() {
+        let mut memory = owned_memory(false);
+        memory.alloc_tagged::<[u8; 64]>("particles");
+        let high_water = memory.used();
+
+        memory.reset();
+        memory.alloc_tagged::<u64>("particles");
+
+        let particles = stats(&memory, "particles");
+        assert_eq!(particles.allocations, 1);
+        assert_eq!(particles.current, 16);
+        assert_eq!(particles.high_water, 64);
+        assert_eq!(memory.high_water(), high_water);
+        assert!(memory.used() < high_water);
+    }
+
+    #[test]
+    fn usage_warning_is_emitted_once() {
+        let mut memory = owned_memory(false);
+        memory.set_warn_threshold(0.5);
+
+        memory.alloc_tagged::<[u8; PAGE_SIZE]>("small");
+        assert!(memory.take_usage_warning().is_none());
+
+        memory.alloc_tagged::<[u8; PAGE_SIZE * 8]>("large");
+        let warning = memory.take_usage_warning().expect("Crossed the threshold");
+        assert_eq!(warning.used, memory.used());
+        assert_eq!(warning.capacity, memory.capacity());
+        assert_eq!(warning.tags.len(), 2);
+
+        // Only reported once until the memory is reset
+        memory.alloc_tagged::<u64>("large");
+        assert!(memory.take_usage_warning().is_none());
+    }
+
+    /// Parse `args` as given on the command line
+    fn parse_args(args: &[&str]) -> Result<MemoryConfig, MemoryError> {
+        MemoryConfig::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn sizes_parse_with_suffixes() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("0xcdcd_0000"), Some(0xcdcd_0000));
+        assert_eq!(parse_size("64k"), Some(64 << 10));
+        assert_eq!(parse_size("2M"), Some(2 << 20));
+        assert_eq!(parse_size("1G"), Some(1 << 30));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("2T"), None);
+        assert_eq!(parse_size("-1"), None);
+    }
+
+    #[test]
+    fn config_from_args() {
+        let config = parse_args(&[
+            "game",
+            "--memory-backend",
+            "owned",
+            "--memory-size",
+            "1M",
+            "--memory-reserve",
+            "0x400000",
+            "--memory-canaries",
+            "--no-memory-guard",
+        ])
+        .unwrap();
+
+        assert_eq!(config.backend, MemoryBackend::Owned);
+        assert_eq!(config.size, 1 << 20);
+        assert_eq!(config.reserve, 0x40_0000);
+        assert!(config.canaries);
+        assert!(!config.guard_pages);
+
+        // The reserve grows to fit a larger size
+        let config = parse_args(&["--memory-backend", "owned", "--memory-size", "128M"]).unwrap();
+        assert_eq!(config.reserve, config.size);
+    }
+
+    #[test]
+    fn bad_args_are_rejected() {
+        for args in [
+            &["--memory-size"][..],
+            &["--memory-size", "lots"],
+            &["--memory-backend", "heap"],
+            &["--memory-backend", "owned", "--memory-size", "1000"],
+            &["--memory-backend", "owned", "--memory-file", "game.mem"],
+        ] {
+            assert!(
+                matches!(parse_args(args), Err(MemoryError::InvalidConfig(_))),
+                "{args:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn resuming_another_schema_is_refused() {
+        let config = MemoryConfig {
+            schema: 2,
+            ..Default::default()
+        };
+        let mut header = MemoryHeader {
+            magic: MEMORY_MAGIC,
+            version: MEMORY_VERSION,
+            initialized: 1,
+            base: config.base as u64,
+            reserve: config.reserve as u64,
+            next_allocation: HEADER_SIZE as u64,
+            generation: 1,
+            schema: 2,
+        };
+        assert_eq!(validate_header(&header, &config), Ok(()));
+
+        header.schema = 1;
+        assert!(matches!(
+            validate_header(&header, &config),
+            Err(MemoryError::IncompatibleImage(_))
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_bad_layouts() {
+        let owned = MemoryConfig {
+            backend: MemoryBackend::Owned,
+            size: 4 * PAGE_SIZE,
+            reserve: 4 * PAGE_SIZE,
+            ..Default::default()
+        };
+        assert_eq!(owned.validate(), Ok(()));
+
+        for config in [
+            MemoryConfig {
+                reserve: 2 * PAGE_SIZE,
+                ..owned.clone()
+            },
+            MemoryConfig {
+                reserve: 4 * PAGE_SIZE + 1,
+                ..owned.clone()
+            },
+            MemoryConfig {
+                size: 0,
+                ..owned.clone()
+            },
+            MemoryConfig {
+                backend: MemoryBackend::Fixed,
+                base: MEMORY_BASE_ADDR + 1,
+                ..owned.clone()
+            },
+            MemoryConfig {
+                backend: MemoryBackend::Fixed,
+                base: PAGE_SIZE,
+                guard_pages: true,
+                ..owned.clone()
+            },
+        ] {
+            assert!(
+                matches!(config.validate(), Err(MemoryError::InvalidConfig(_))),
+                "{config:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn canaries_catch_writes_around_allocations() {
+        let mut memory = owned_memory(true);
+        memory.alloc_tagged::<[u8; 8]>("clean");
+        memory.alloc_tagged::<[u8; 8]>("buggy");
+        assert!(memory.check_canaries().is_empty());
+
+        // Write one byte past the end and two bytes before the start of "buggy"
+        let record = memory.allocations()[1].clone();
+        let value = (memory.base() + record.offset) as *mut u8;
+        unsafe {
+            value.add(record.size).write(0);
+            value.sub(2).write(0);
+        }
+
+        let corruptions = memory.check_canaries();
+        assert_eq!(corruptions.len(), 2);
+        assert!(corruptions
+            .iter()
+            .all(|corruption| corruption.tag == "buggy"));
+
+        let before = corruptions.iter().find(|corruption| !corruption.overflow);
+        assert_eq!(before.unwrap().offset, record.offset - 2);
+
+        let after = corruptions.iter().find(|corruption| corruption.overflow);
+        assert_eq!(after.unwrap().offset, record.offset + record.size);
+
+        // The canaries are repaired so each corruption is only reported once
+        assert!(memory.check_canaries().is_empty());
     }
 }
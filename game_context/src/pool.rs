@@ -0,0 +1,355 @@
+//! Fixed capacity pool of objects in the game memory with O(1) insert and remove
+
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::addr_of_mut;
+
+use crate::*;
+
+/// Marks the end of the free list
+const FREE_LIST_END: u32 = u32::MAX;
+
+/// A single slot of a [`Pool`]
+struct Slot<T> {
+    /// Incremented every time the slot is freed so old handles can't access it
+    generation: u32,
+
+    /// Index of the next free slot while this slot is free
+    next_free: u32,
+
+    /// Is a value currently stored in this slot
+    live: bool,
+
+    /// The stored value, only initialized while `live`
+    value: MaybeUninit<T>,
+}
+
+/// The pool data as stored in the game memory
+struct PoolStorage<T, const N: usize> {
+    /// Number of live values
+    len: u32,
+
+    /// Index of the first free slot
+    free_head: u32,
+
+    /// All slots of the pool
+    slots: [Slot<T>; N],
+}
+
+/// Handle to a value in a [`Pool`]
+#[derive(Debug)]
+pub struct PoolHandle<T> {
+    /// Index of the slot holding the value
+    index: u32,
+
+    /// Generation of the slot when the value was inserted
+    generation: u32,
+
+    /// The type of the value
+    phantom: PhantomData<T>,
+}
+
+impl<T> Clone for PoolHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PoolHandle<T> {}
+
+impl<T> PartialEq for PoolHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for PoolHandle<T> {}
+
+/// A pool of up to `N` values of `T` carved out of the game memory
+///
+/// The pool lives in the game memory, so it survives a hot reload. Values still in the
+/// pool on a [`Memory::reset`] are leaked and the pool itself becomes invalid: it is
+/// empty and every insert fails.
+///
+/// The pool is not `Clone` since both copies would share the same slots.
+pub struct Pool<T, const N: usize> {
+    /// The slots in the game memory
+    storage: Allocation<PoolStorage<T, N>>,
+}
+
+impl<T, const N: usize> core::fmt::Debug for Pool<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pool")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Pool<T, N> {
+    /// Allocate an empty pool in `memory`, accounting its usage to `tag`
+    pub fn new(memory: &mut Memory, tag: &str) -> Self {
+        assert!(N < FREE_LIST_END as usize, "Pool capacity too large");
+
+        let mut storage = memory.alloc_tagged::<PoolStorage<T, N>>(tag);
+        let ptr = storage
+            .as_mut_ptr()
+            .expect("A new allocation is always valid");
+
+        // The memory may hold garbage from before a reset (an invalid `bool` even), so
+        // every field is written through raw pointers without creating a reference
+        // until the storage is fully initialized. The values are left uninitialized.
+        //
+        // SAFETY: `ptr` is a valid allocation of `PoolStorage<T, N>`
+        unsafe {
+            addr_of_mut!((*ptr).len).write(0);
+            addr_of_mut!((*ptr).free_head).write(if N == 0 { FREE_LIST_END } else { 0 });
+
+            let slots = addr_of_mut!((*ptr).slots) as *mut Slot<T>;
+            for index in 0..N {
+                let slot = slots.add(index);
+                addr_of_mut!((*slot).generation).write(0);
+                addr_of_mut!((*slot).live).write(false);
+                addr_of_mut!((*slot).next_free).write(if index + 1 < N {
+                    index as u32 + 1
+                } else {
+                    FREE_LIST_END
+                });
+            }
+        }
+
+        Self { storage }
+    }
+
+    /// Returns `true` if the pool was allocated in the current generation of its memory
+    pub fn is_valid(&self) -> bool {
+        self.storage.is_valid()
+    }
+
+    /// Number of values in the pool, zero if the pool is invalid
+    pub fn len(&self) -> usize {
+        self.storage.get().map_or(0, |storage| storage.len as usize)
+    }
+
+    /// Returns `true` if the pool has no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of values the pool can hold
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Insert `value` into a free slot, returning `None` if the pool is full or invalid
+    pub fn insert(&mut self, value: T) -> Option<PoolHandle<T>> {
+        let storage = self.storage.get_mut()?;
+
+        if storage.free_head == FREE_LIST_END {
+            return None;
+        }
+
+        // Pop the first free slot
+        let index = storage.free_head;
+        let slot = &mut storage.slots[index as usize];
+        storage.free_head = slot.next_free;
+        storage.len += 1;
+
+        slot.live = true;
+        slot.value.write(value);
+
+        Some(PoolHandle {
+            index,
+            generation: slot.generation,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Remove the value for `handle`, returning `None` if it was already removed
+    pub fn remove(&mut self, handle: PoolHandle<T>) -> Option<T> {
+        let storage = self.storage.get_mut()?;
+        let slot = storage.slots.get_mut(handle.index as usize)?;
+
+        if !slot.live || slot.generation != handle.generation {
+            return None;
+        }
+
+        // Invalidate every handle to this slot and push it on the free list
+        slot.live = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.next_free = storage.free_head;
+        storage.free_head = handle.index;
+        storage.len -= 1;
+
+        // SAFETY: The slot was live, so the value is initialized
+        Some(unsafe { slot.value.assume_init_read() })
+    }
+
+    /// Returns `true` if the value for `handle` is still in the pool
+    pub fn contains(&self, handle: PoolHandle<T>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Get the value for `handle`, or `None` if it was removed
+    pub fn get(&self, handle: PoolHandle<T>) -> Option<&T> {
+        let slot = self.storage.get()?.slots.get(handle.index as usize)?;
+
+        if !slot.live || slot.generation != handle.generation {
+            return None;
+        }
+
+        // SAFETY: The slot is live, so the value is initialized
+        Some(unsafe { slot.value.assume_init_ref() })
+    }
+
+    /// Get a mutable reference to the value for `handle`, or `None` if it was removed
+    pub fn get_mut(&mut self, handle: PoolHandle<T>) -> Option<&mut T> {
+        let slot = self
+            .storage
+            .get_mut()?
+            .slots
+            .get_mut(handle.index as usize)?;
+
+        if !slot.live || slot.generation != handle.generation {
+            return None;
+        }
+
+        // SAFETY: The slot is live, so the value is initialized
+        Some(unsafe { slot.value.assume_init_mut() })
+    }
+
+    /// Iterate over every value in the pool
+    pub fn iter(&self) -> impl Iterator<Item = (PoolHandle<T>, &T)> {
+        self.storage
+            .get()
+            .into_iter()
+            .flat_map(|storage| storage.slots.iter())
+            .enumerate()
+            .filter(|(_, slot)| slot.live)
+            .map(|(index, slot)| {
+                let handle = PoolHandle {
+                    index: index as u32,
+                    generation: slot.generation,
+                    phantom: PhantomData,
+                };
+
+                // SAFETY: The slot is live, so the value is initialized
+                (handle, unsafe { slot.value.assume_init_ref() })
+            })
+    }
+
+    /// Iterate mutably over every value in the pool
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (PoolHandle<T>, &mut T)> {
+        self.storage
+            .get_mut()
+            .into_iter()
+            .flat_map(|storage| storage.slots.iter_mut())
+            .enumerate()
+            .filter(|(_, slot)| slot.live)
+            .map(|(index, slot)| {
+                let handle = PoolHandle {
+                    index: index as u32,
+                    generation: slot.generation,
+                    phantom: PhantomData,
+                };
+
+                // SAFETY: The slot is live, so the value is initialized
+                (handle, unsafe { slot.value.assume_init_mut() })
+            })
+    }
+
+    /// Remove every value for which `keep` returns `false`
+    pub fn retain(&mut self, mut keep: impl FnMut(&mut T) -> bool) {
+        let dead: Vec<PoolHandle<T>> = self
+            .iter_mut()
+            .filter_map(|(handle, value)| (!keep(value)).then_some(handle))
+            .collect();
+
+        for handle in dead {
+            self.remove(handle);
+        }
+    }
+
+    /// Remove every value in the pool
+    pub fn clear(&mut self) {
+        self.retain(|_| false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::tests::owned_memory;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut memory = owned_memory(false);
+        let mut pool = Pool::<u32, 4>::new(&mut memory, "pool");
+        assert!(pool.is_empty());
+
+        let a = pool.insert(1).unwrap();
+        let b = pool.insert(2).unwrap();
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.get(a), Some(&1));
+
+        *pool.get_mut(b).unwrap() += 10;
+        assert_eq!(pool.remove(b), Some(12));
+        assert_eq!(pool.len(), 1);
+        assert!(!pool.contains(b));
+
+        let values: Vec<u32> = pool.iter().map(|(_, value)| *value).collect();
+        assert_eq!(values, [1]);
+    }
+
+    #[test]
+    fn freed_slot_is_reused_with_new_generation() {
+        let mut memory = owned_memory(false);
+        let mut pool = Pool::<u32, 4>::new(&mut memory, "pool");
+
+        let old = pool.insert(1).unwrap();
+        pool.remove(old);
+        let new = pool.insert(2).unwrap();
+
+        // Same slot, but the old handle must not see the new value
+        assert_eq!(new.index, old.index);
+        assert_eq!(new.generation, old.generation + 1);
+        assert_eq!(pool.get(old), None);
+        assert_eq!(pool.get_mut(old), None);
+        assert_eq!(pool.remove(old), None);
+        assert_eq!(pool.get(new), Some(&2));
+    }
+
+    #[test]
+    fn full_pool_rejects_inserts() {
+        let mut memory = owned_memory(false);
+        let mut pool = Pool::<u32, 2>::new(&mut memory, "pool");
+
+        let first = pool.insert(1).unwrap();
+        pool.insert(2).unwrap();
+        assert_eq!(pool.insert(3), None);
+        assert_eq!(pool.len(), pool.capacity());
+
+        // Removing makes room again
+        pool.remove(first);
+        assert!(pool.insert(3).is_some());
+
+        pool.clear();
+        assert!(pool.is_empty());
+        assert_eq!(pool.iter().count(), 0);
+    }
+
+    #[test]
+    fn pool_is_invalid_after_reset() {
+        let mut memory = owned_memory(false);
+        let mut pool = Pool::<u32, 2>::new(&mut memory, "pool");
+        let handle = pool.insert(1).unwrap();
+
+        memory.reset();
+
+        assert!(!pool.is_valid());
+        assert!(pool.is_empty());
+        assert_eq!(pool.get(handle), None);
+        assert_eq!(pool.insert(2), None);
+        assert_eq!(pool.iter_mut().count(), 0);
+    }
+}
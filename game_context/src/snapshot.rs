@@ -0,0 +1,495 @@
+//! Compare two snapshots of the game memory taken with [`Memory::data_as_vec`]
+
+use core::ops::Range;
+use std::collections::HashMap;
+
+use crate::*;
+
+/// A type whose fields can be compared between two memory snapshots
+pub trait Inspect {
+    /// The name and formatted value of each field of this value
+    fn fields(&self) -> Vec<(String, String)>;
+}
+
+/// Implement [`Inspect`] as a single `value` field using the `Debug` format
+macro_rules! impl_inspect_debug {
+    ($($ty:ty),*) => {
+        $(
+            impl Inspect for $ty {
+                fn fields(&self) -> Vec<(String, String)> {
+                    vec![("value".to_string(), format!("{self:?}"))]
+                }
+            }
+        )*
+    };
+}
+
+impl_inspect_debug!(
+    bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, Vec2, Color
+);
+
+/// Implement [`Inspect`] for a struct by listing the fields to diff, each formatted
+/// with `Debug`, such as `inspect_fields!(Player { position, health });`
+#[macro_export]
+macro_rules! inspect_fields {
+    ($ty:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::Inspect for $ty {
+            fn fields(&self) -> Vec<(String, String)> {
+                vec![$((stringify!($field).to_string(), format!("{:?}", self.$field))),*]
+            }
+        }
+    };
+}
+
+/// Plain data that can be read out of any snapshot bytes
+///
+/// A snapshot can hold anything, such as memory from before a [`Memory::reset`] or from
+/// a type with a different layout, so only types without invalid values can be diffed.
+///
+/// # Safety
+///
+/// Every bit pattern of `size_of::<Self>()` bytes must be a valid `Self`. This rules out
+/// `bool`, `char`, enums, references and any type containing them.
+pub unsafe trait Pod: Copy + 'static {}
+
+/// Implement [`Pod`] for types that are valid for every bit pattern
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {
+        $(
+            // SAFETY: Integers, floats and structs made only of floats have no invalid
+            //         bit patterns
+            unsafe impl Pod for $ty {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, Vec2, Color);
+
+/// Reads the fields of a `T` out of snapshot bytes
+type InspectFn = fn(&[u8]) -> Vec<(String, String)>;
+
+/// Read a `T` from `bytes` and return its fields
+fn inspect_bytes<T: Inspect + Pod>(bytes: &[u8]) -> Vec<(String, String)> {
+    assert!(bytes.len() >= core::mem::size_of::<T>());
+
+    // SAFETY: `T` is `Pod`, so any bytes are a valid `T`. Snapshot bytes are not
+    //         aligned, so the read must be unaligned.
+    let value = unsafe { core::ptr::read_unaligned(bytes.as_ptr().cast::<T>()) };
+
+    value.fields()
+}
+
+/// Types that get a field level diff, keyed by their type name
+///
+/// The registry holds function pointers into whichever library registered the types,
+/// so it must be rebuilt after a hot reload of that library
+#[derive(Clone)]
+pub struct DiffRegistry {
+    types: HashMap<String, InspectFn>,
+}
+
+impl Default for DiffRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            types: HashMap::new(),
+        };
+
+        registry.register::<u32>();
+        registry.register::<u64>();
+        registry.register::<usize>();
+        registry.register::<i32>();
+        registry.register::<i64>();
+        registry.register::<f32>();
+        registry.register::<f64>();
+        registry.register::<Vec2>();
+        registry.register::<Color>();
+
+        registry
+    }
+}
+
+impl DiffRegistry {
+    /// Diff the fields of all allocations of `T`
+    pub fn register<T: Inspect + Pod>(&mut self) {
+        self.types
+            .insert(core::any::type_name::<T>().to_string(), inspect_bytes::<T>);
+    }
+}
+
+/// A field that changed between two snapshots
+#[derive(Debug, Clone)]
+pub struct FieldDiff {
+    /// Name of the field
+    pub name: String,
+
+    /// The formatted value in the first snapshot
+    pub before: String,
+
+    /// The formatted value in the second snapshot
+    pub after: String,
+}
+
+/// The changes to a single allocation between two snapshots
+#[derive(Debug, Clone)]
+pub struct AllocationDiff {
+    /// The tag given to the allocation
+    pub tag: String,
+
+    /// The name of the allocated type
+    pub type_name: String,
+
+    /// Offset of the allocation from the memory base
+    pub offset: usize,
+
+    /// Size of the allocation
+    pub size: usize,
+
+    /// Changed byte ranges, relative to the start of the allocation
+    pub ranges: Vec<Range<usize>>,
+
+    /// Changed fields if the type is registered in the [`DiffRegistry`]
+    pub fields: Vec<FieldDiff>,
+}
+
+/// The changes to a value living outside of the game memory, such as the game state
+/// owned by the host
+#[derive(Debug, Clone)]
+pub struct ValueDiff {
+    /// Name of the value
+    pub name: String,
+
+    /// Changed fields of the value
+    pub fields: Vec<FieldDiff>,
+}
+
+/// All changes between two memory snapshots
+#[derive(Debug, Clone, Default)]
+pub struct MemoryDiff {
+    /// Allocations with at least one changed byte
+    pub allocations: Vec<AllocationDiff>,
+
+    /// Changed byte ranges (from the memory base) not belonging to any allocation
+    pub untracked: Vec<Range<usize>>,
+
+    /// Values outside of the game memory with at least one changed field
+    pub values: Vec<ValueDiff>,
+}
+
+/// Pair up the fields of two inspections of the same type, keeping the changed ones
+fn changed_fields(before: Vec<(String, String)>, after: Vec<(String, String)>) -> Vec<FieldDiff> {
+    before
+        .into_iter()
+        .zip(after)
+        .filter(|((_, before), (_, after))| before != after)
+        .map(|((name, before), (_, after))| FieldDiff {
+            name,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Get the ranges of bytes that differ between `before` and `after`. Bytes beyond the
+/// shorter snapshot are considered changed.
+fn changed_ranges(before: &[u8], after: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let len = before.len().max(after.len());
+
+    for offset in 0..len {
+        if before.get(offset) == after.get(offset) {
+            continue;
+        }
+
+        // Extend the previous range if this byte is adjacent to it
+        match ranges.last_mut() {
+            Some(range) if range.end == offset => range.end += 1,
+            _ => ranges.push(offset..offset + 1),
+        }
+    }
+
+    ranges
+}
+
+impl MemoryDiff {
+    /// Returns `true` if nothing changed between the snapshots
+    pub fn is_empty(&self) -> bool {
+        self.allocations.is_empty() && self.untracked.is_empty() && self.values.is_empty()
+    }
+
+    /// Add the changed fields of a value that lives outside of the game memory, since
+    /// the memory snapshots can't see it
+    pub fn diff_value<T: Inspect>(&mut self, name: &str, before: &T, after: &T) {
+        let fields = changed_fields(before.fields(), after.fields());
+        if !fields.is_empty() {
+            self.values.push(ValueDiff {
+                name: name.to_string(),
+                fields,
+            });
+        }
+    }
+
+    /// Draw the changed allocations in the top right of the screen
+    pub fn draw_overlay(&self, macroquad: &Macroquad) {
+        const X: f32 = SCREEN_WIDTH / 2.;
+        const LINE_HEIGHT: f32 = 0.6;
+        const MAX_LINES: usize = 30;
+
+        let (font_size, font_scale, font_aspect) = (macroquad.camera_font_scale)(0.5);
+        let text_params = |color| TextParams {
+            font_size,
+            font_scale,
+            font_scale_aspect: font_aspect,
+            color,
+            ..Default::default()
+        };
+
+        let mut lines = vec![(
+            format!(
+                "diff: {} allocations, {} untracked ranges, {} values",
+                self.allocations.len(),
+                self.untracked.len(),
+                self.values.len()
+            ),
+            WHITE,
+        )];
+
+        for value in &self.values {
+            lines.push((value.name.clone(), SKYBLUE));
+
+            for field in &value.fields {
+                lines.push((
+                    format!("  {}: {} -> {}", field.name, field.before, field.after),
+                    WHITE,
+                ));
+            }
+        }
+
+        for allocation in &self.allocations {
+            let changed: usize = allocation.ranges.iter().map(|range| range.len()).sum();
+            lines.push((
+                format!(
+                    "{} ({}) @ {:#x}: {changed} / {} bytes",
+                    allocation.tag, allocation.type_name, allocation.offset, allocation.size
+                ),
+                YELLOW,
+            ));
+
+            for field in &allocation.fields {
+                lines.push((
+                    format!("  {}: {} -> {}", field.name, field.before, field.after),
+                    WHITE,
+                ));
+            }
+        }
+
+        for range in &self.untracked {
+            lines.push((
+                format!("untracked {:#x}..{:#x}", range.start, range.end),
+                RED,
+            ));
+        }
+
+        let mut y = 1.4;
+        for (line, color) in lines.iter().take(MAX_LINES) {
+            (macroquad.draw_text_ex)(line, X, y, text_params(*color));
+            y += LINE_HEIGHT;
+        }
+    }
+}
+
+impl Memory {
+    /// Compare two snapshots of this memory from [`Memory::data_as_vec`], mapping each
+    /// changed byte back to the allocation containing it
+    pub fn diff(&self, before: &[u8], after: &[u8], registry: &DiffRegistry) -> MemoryDiff {
+        let mut diff = MemoryDiff::default();
+
+        for range in changed_ranges(before, after) {
+            let mut start = range.start;
+
+            // Split the range across every allocation it touches
+            while start < range.end {
+                let record = self
+                    .allocations()
+                    .iter()
+                    .find(|record| (record.offset..record.offset + record.size).contains(&start));
+
+                let Some(record) = record else {
+                    // Extend the untracked range until the next allocation
+                    let end = self
+                        .allocations()
+                        .iter()
+                        .map(|record| record.offset)
+                        .filter(|offset| *offset > start)
+                        .min()
+                        .unwrap_or(range.end)
+                        .min(range.end);
+
+                    match diff.untracked.last_mut() {
+                        Some(untracked) if untracked.end == start => untracked.end = end,
+                        _ => diff.untracked.push(start..end),
+                    }
+
+                    start = end;
+                    continue;
+                };
+
+                let end = range.end.min(record.offset + record.size);
+                let relative = start - record.offset..end - record.offset;
+
+                match diff.allocations.last_mut() {
+                    Some(allocation) if allocation.offset == record.offset => {
+                        allocation.ranges.push(relative);
+                    }
+                    _ => diff.allocations.push(AllocationDiff {
+                        tag: record.tag.clone(),
+                        type_name: record.type_name.clone(),
+                        offset: record.offset,
+                        size: record.size,
+                        ranges: vec![relative],
+                        fields: Vec::new(),
+                    }),
+                }
+
+                start = end;
+            }
+        }
+
+        // Add the field level diff for registered types
+        for allocation in &mut diff.allocations {
+            let Some(inspect) = registry.types.get(&allocation.type_name) else {
+                continue;
+            };
+
+            let bytes = allocation.offset..allocation.offset + allocation.size;
+            let (Some(old), Some(new)) = (before.get(bytes.clone()), after.get(bytes)) else {
+                continue;
+            };
+
+            allocation.fields = changed_fields(inspect(old), inspect(new));
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A multi-field type diffed field by field
+    #[derive(Debug, Clone, Copy)]
+    #[repr(C)]
+    struct Player {
+        position: Vec2,
+        score: u32,
+        speed: f32,
+    }
+
+    inspect_fields!(Player {
+        position,
+        score,
+        speed
+    });
+
+    // SAFETY: Only made of floats and integers, padded to the alignment by `repr(C)`
+    unsafe impl Pod for Player {}
+
+    #[test]
+    fn equal_snapshots() {
+        assert!(changed_ranges(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert!(changed_ranges(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn single_changed_byte() {
+        assert_eq!(changed_ranges(&[1, 2, 3, 4], &[1, 2, 9, 4]), [2..3]);
+    }
+
+    #[test]
+    fn adjacent_bytes_merge() {
+        assert_eq!(
+            changed_ranges(&[0, 0, 0, 0, 0, 0], &[1, 1, 0, 1, 1, 1]),
+            [0..2, 3..6]
+        );
+    }
+
+    #[test]
+    fn length_mismatch() {
+        assert_eq!(changed_ranges(&[1, 2], &[1, 2, 3, 4]), [2..4]);
+        assert_eq!(changed_ranges(&[1, 2, 3, 4], &[1, 9]), [1..4]);
+    }
+
+    #[test]
+    fn field_diff() {
+        let mut memory = crate::memory::tests::owned_memory(false);
+        let mut player = memory.alloc_tagged::<Player>("player");
+        *player = Player {
+            position: Vec2::new(1., 2.),
+            score: 1,
+            speed: 3.,
+        };
+
+        let before = memory.data_as_vec();
+        player.score = 2;
+        let after = memory.data_as_vec();
+
+        let mut registry = DiffRegistry::default();
+        registry.register::<Player>();
+
+        let diff = memory.diff(&before, &after, &registry);
+        assert!(diff.untracked.is_empty());
+        assert_eq!(diff.allocations.len(), 1);
+
+        let allocation = &diff.allocations[0];
+        assert_eq!(allocation.tag, "player");
+        assert_eq!(allocation.ranges, [8..9]);
+        assert_eq!(allocation.fields.len(), 1);
+        assert_eq!(allocation.fields[0].name, "score");
+        assert_eq!(
+            (
+                allocation.fields[0].before.as_str(),
+                allocation.fields[0].after.as_str()
+            ),
+            ("1", "2")
+        );
+    }
+
+    #[test]
+    fn unregistered_types_only_diff_bytes() {
+        let mut memory = crate::memory::tests::owned_memory(false);
+        let mut flag = memory.alloc::<bool>();
+        *flag = false;
+
+        let before = memory.data_as_vec();
+        *flag = true;
+        let after = memory.data_as_vec();
+
+        let diff = memory.diff(&before, &after, &DiffRegistry::default());
+        assert_eq!(diff.allocations.len(), 1);
+        assert_eq!(diff.allocations[0].ranges, [0..1]);
+        assert!(diff.allocations[0].fields.is_empty());
+    }
+
+    #[test]
+    fn values_outside_memory_diff_by_field() {
+        let before = Player {
+            position: Vec2::ZERO,
+            score: 1,
+            speed: 2.,
+        };
+        let after = Player {
+            position: Vec2::ONE,
+            ..before
+        };
+
+        let mut diff = MemoryDiff::default();
+        diff.diff_value("player", &before, &before);
+        assert!(diff.is_empty());
+
+        diff.diff_value("player", &before, &after);
+        assert_eq!(diff.values.len(), 1);
+        assert_eq!(diff.values[0].name, "player");
+        assert_eq!(diff.values[0].fields.len(), 1);
+        assert_eq!(diff.values[0].fields[0].name, "position");
+    }
+}
@@ -0,0 +1,297 @@
+//! Debug screen plotting every ease function with a live preview of each
+
+use crate::*;
+
+/// Number of curves drawn per row
+const COLUMNS: usize = 6;
+
+/// Height of the parameter header above the curves
+const HEADER_HEIGHT: f32 = 1.2;
+
+/// Padding around each curve
+const PADDING: f32 = 0.15;
+
+/// Seconds each ease takes to play
+const PLAY_TIME: f32 = 1.;
+
+/// Seconds to hold at the target before playing again
+const HOLD_TIME: f32 = 0.5;
+
+/// A parameter of the parameterized eases that can be adjusted live
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Parameter {
+    Overshoot,
+    Elastic,
+    Bounciness,
+    Bounces,
+}
+
+impl Parameter {
+    /// Every parameter in the order they are selected
+    const ALL: [Parameter; 4] = [
+        Parameter::Overshoot,
+        Parameter::Elastic,
+        Parameter::Bounciness,
+        Parameter::Bounces,
+    ];
+}
+
+/// Plots every [`EaseFunc`] with a dot moving along the curve and a box moving with it
+#[derive(Debug, Clone)]
+pub struct EaseVisualizer {
+    /// Seconds since the visualizer started playing
+    time: f32,
+
+    /// Index of the parameter in [`Parameter::ALL`] adjusted by Left and Right
+    selected: usize,
+
+    /// The `overshoot` of the back eases
+    overshoot: f32,
+
+    /// The `elastic` of the elastic eases
+    elastic: f32,
+
+    /// The `bounciness` of the bounce eases
+    bounciness: f32,
+
+    /// The `bounces` of the bounce eases
+    bounces: u32,
+
+    /// The buttons held last frame, to only react to new presses
+    held: Vec<KeyCode>,
+}
+
+impl Default for EaseVisualizer {
+    fn default() -> Self {
+        Self {
+            time: 0.,
+            selected: 0,
+            overshoot: EaseFunc::BACK_OVERSHOOT,
+            elastic: EaseFunc::ELASTIC,
+            bounciness: EaseFunc::BOUNCINESS,
+            bounces: EaseFunc::BOUNCES,
+            held: Vec::new(),
+        }
+    }
+}
+
+impl EaseVisualizer {
+    /// Advance the preview and adjust the parameters with the arrow keys
+    pub fn update(&mut self, buttons: &[KeyCode], frame_time: f32) {
+        self.time = (self.time + frame_time) % (PLAY_TIME + HOLD_TIME);
+
+        let pressed = |key| buttons.contains(&key) && !self.held.contains(&key);
+        let (up, down, left, right) = (
+            pressed(KeyCode::Up),
+            pressed(KeyCode::Down),
+            pressed(KeyCode::Left),
+            pressed(KeyCode::Right),
+        );
+
+        // Up/Down - select the parameter
+        let count = Parameter::ALL.len();
+        if up {
+            self.selected = (self.selected + count - 1) % count;
+        }
+
+        if down {
+            self.selected = (self.selected + 1) % count;
+        }
+
+        // Left/Right - decrease/increase the selected parameter
+        let direction = right as i32 - left as i32;
+        if direction != 0 {
+            let step = direction as f32;
+
+            match Parameter::ALL[self.selected] {
+                Parameter::Overshoot => {
+                    self.overshoot = (self.overshoot + step * 0.25).clamp(0., 5.);
+                }
+                Parameter::Elastic => self.elastic = (self.elastic + step * 0.5).clamp(0.5, 20.),
+                Parameter::Bounciness => {
+                    self.bounciness = (self.bounciness + step * 0.05).clamp(0.05, 0.95);
+                }
+                Parameter::Bounces => {
+                    self.bounces = self.bounces.saturating_add_signed(direction).min(10);
+                }
+            }
+        }
+
+        self.held.clear();
+        self.held.extend_from_slice(buttons);
+    }
+
+    /// Every ease function using the current parameters
+    pub fn eases(&self) -> Vec<EaseFunc> {
+        let Self {
+            overshoot,
+            elastic,
+            bounciness,
+            bounces,
+            ..
+        } = *self;
+
+        vec![
+            EaseFunc::Linear,
+            EaseFunc::SmoothStart2,
+            EaseFunc::SmoothStart3,
+            EaseFunc::SmoothStart4,
+            EaseFunc::SmoothStart5,
+            EaseFunc::SmoothStop2,
+            EaseFunc::SmoothStop3,
+            EaseFunc::SmoothStop4,
+            EaseFunc::SmoothStop5,
+            EaseFunc::SmoothStep2,
+            EaseFunc::SmoothStep3,
+            EaseFunc::SmoothStep4,
+            EaseFunc::SmoothStep5,
+            EaseFunc::SineStart,
+            EaseFunc::SineStop,
+            EaseFunc::SineStep,
+            EaseFunc::ExpoStart,
+            EaseFunc::ExpoStop,
+            EaseFunc::ExpoStep,
+            EaseFunc::CircStart,
+            EaseFunc::CircStop,
+            EaseFunc::CircStep,
+            EaseFunc::BackStart { overshoot },
+            EaseFunc::BackStop { overshoot },
+            EaseFunc::BackStep { overshoot },
+            EaseFunc::ElasticStart { elastic },
+            EaseFunc::ElasticStop { elastic },
+            EaseFunc::ElasticStep { elastic },
+            EaseFunc::BounceStart {
+                bounciness,
+                bounces,
+            },
+            EaseFunc::BounceStop {
+                bounciness,
+                bounces,
+            },
+            EaseFunc::BounceStep {
+                bounciness,
+                bounces,
+            },
+            // CSS `ease`
+            EaseFunc::CubicBezier {
+                x1: 0.25,
+                y1: 0.1,
+                x2: 0.25,
+                y2: 1.,
+            },
+            EaseFunc::SmoothStop3.reverse(),
+            EaseFunc::SmoothStop2.mirror(),
+            EaseFunc::SmoothStop2.blend(EaseFunc::ElasticStop { elastic }, 0.5),
+            EaseFunc::SmoothStart3.crossfade(EaseFunc::BackStop { overshoot }),
+            EaseFunc::SmoothStart2.chain(EaseFunc::ElasticStop { elastic }, 0.5),
+            EaseFunc::SineStop.scale(0.5, 0.25),
+        ]
+    }
+
+    /// Draw a grid with a curve for each of the `eases`
+    pub fn draw(&self, macroquad: &Macroquad, eases: &[EaseFunc]) {
+        let (font_size, font_scale, font_aspect) = (macroquad.camera_font_scale)(0.4);
+        let text_params = |color| TextParams {
+            font_size,
+            font_scale,
+            font_scale_aspect: font_aspect,
+            color,
+            ..Default::default()
+        };
+
+        // Header with the adjustable parameters, highlighting the selected one
+        let mut x = 0.3;
+        for (index, parameter) in Parameter::ALL.iter().enumerate() {
+            let text = match parameter {
+                Parameter::Overshoot => format!("overshoot {:.2}", self.overshoot),
+                Parameter::Elastic => format!("elastic {:.1}", self.elastic),
+                Parameter::Bounciness => format!("bounciness {:.2}", self.bounciness),
+                Parameter::Bounces => format!("bounces {}", self.bounces),
+            };
+
+            let color = if index == self.selected {
+                YELLOW
+            } else {
+                WHITE
+            };
+
+            (macroquad.draw_text_ex)(&text, x, 0.5, text_params(color));
+            x += 4.;
+        }
+
+        (macroquad.draw_text_ex)(
+            "Up/Down: select  Left/Right: adjust",
+            0.3,
+            1.,
+            text_params(GRAY),
+        );
+
+        if eases.is_empty() {
+            return;
+        }
+
+        let rows = eases.len().div_ceil(COLUMNS);
+        let cell_width = SCREEN_WIDTH / COLUMNS as f32;
+        let cell_height = (SCREEN_HEIGHT - HEADER_HEIGHT) / rows as f32;
+        let t = (self.time / PLAY_TIME).min(1.);
+
+        for (index, ease) in eases.iter().enumerate() {
+            let cell_x = (index % COLUMNS) as f32 * cell_width;
+            let cell_y = HEADER_HEIGHT + (index / COLUMNS) as f32 * cell_height;
+
+            // Leave room for the name on top and for overshooting eases above and below
+            let left = cell_x + PADDING;
+            let width = cell_width - 2. * PADDING;
+            let top = cell_y + 0.55;
+            let height = cell_height - 0.55 - PADDING;
+            let plot_height = height / 2.;
+            let baseline = top + height * 0.75;
+
+            // Position of the point (t, value) of the curve
+            let point = |t: f32, value: f32| (left + t * width, baseline - value * plot_height);
+
+            (macroquad.draw_rectangle)(
+                cell_x + PADDING / 2.,
+                cell_y + PADDING / 2.,
+                cell_width - PADDING,
+                cell_height - PADDING,
+                Color::new(0.15, 0.15, 0.15, 1.),
+            );
+
+            (macroquad.draw_text_ex)(ease.name(), left, cell_y + 0.45, text_params(WHITE));
+
+            // Guides at the start and target values
+            for value in [0., 1.] {
+                let (x0, y) = point(0., value);
+                let (x1, _) = point(1., value);
+                (macroquad.draw_line)(x0, y, x1, y, 0.02, DARKGRAY);
+            }
+
+            // The curve itself
+            const SEGMENTS: usize = 48;
+            let mut previous = point(0., ease.calculate(0.));
+            for segment in 1..=SEGMENTS {
+                let t = segment as f32 / SEGMENTS as f32;
+                let next = point(t, ease.calculate(t));
+                (macroquad.draw_line)(previous.0, previous.1, next.0, next.1, 0.04, SKYBLUE);
+                previous = next;
+            }
+
+            let value = ease.calculate(t);
+
+            // Dot moving along the curve
+            let (dot_x, dot_y) = point(t, value);
+            (macroquad.draw_circle)(dot_x, dot_y, 0.08, RED);
+
+            // Box moving across the cell with the ease
+            let size = 0.25;
+            (macroquad.draw_rectangle)(
+                left + value * (width - size),
+                baseline + 0.05,
+                size,
+                size,
+                YELLOW,
+            );
+        }
+    }
+}
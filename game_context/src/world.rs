@@ -1,5 +1,7 @@
 //! Collection of all objects in the world
 
+use std::collections::VecDeque;
+
 use crate::*;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -8,6 +10,27 @@ pub enum Shape {
     Rectangle { width: f32, height: f32 },
 }
 
+/// The kind of a [`Shape`] without its dimensions, used to filter objects
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShapeKind {
+    Circle,
+    Rectangle,
+}
+
+impl Shape {
+    /// Get the kind of this shape
+    pub fn kind(&self) -> ShapeKind {
+        match self {
+            Shape::Circle { .. } => ShapeKind::Circle,
+            Shape::Rectangle { .. } => ShapeKind::Rectangle,
+        }
+    }
+}
+
+/// Game defined category of an object, used to filter objects
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Tag(pub u32);
+
 #[derive(Default, Debug, Clone)]
 pub struct World {
     /// Positions of all objects in the world
@@ -19,36 +42,187 @@ pub struct World {
     /// Colors of an object
     colors: Vec<Color>,
 
+    /// Tags of an object
+    tags: Vec<Tag>,
+
+    /// Rotation (in radians) of an object around its pivot
+    rotations: Vec<f32>,
+
+    /// Non-uniform scale of an object around its pivot
+    scales: Vec<Vec2>,
+
+    /// Point that an object rotates and scales around, relative to its position
+    pivots: Vec<Vec2>,
+
+    /// Generation of each slot, incremented when its object is removed
+    generations: Vec<u32>,
+
+    /// Does this slot currently hold an object
+    alive: Vec<bool>,
+
+    /// Slots of removed objects available for reuse
+    free: Vec<usize>,
+
     /// Is the current object being animated
     pub animating: Vec<bool>,
 
-    /// The total time of this animation
-    animation_duration: Vec<f32>,
+    /// The position track of an object
+    position_tracks: Vec<Tween<Vec2>>,
+
+    /// The object (and offset from it) that the position track of an object follows
+    follows: Vec<Option<(ObjectIndex, Vec2)>>,
+
+    /// The rotation track of an object
+    rotation_tracks: Vec<Tween<f32>>,
+
+    /// The scale track of an object
+    scale_tracks: Vec<Tween<Vec2>>,
 
-    /// The elapsed time of this animation
-    animation_elapsed: Vec<f32>,
+    /// The color track of an object
+    color_tracks: Vec<Tween<Color>>,
 
-    /// The starting animation position for this object
-    animation_position_start: Vec<Vec2>,
+    /// The color space used to interpolate the color track of an object
+    color_modes: Vec<ColorMode>,
 
-    /// The target position for this object to finish by the end of the animation
-    animation_position_target: Vec<Vec2>,
+    /// The alpha track of an object
+    alpha_tracks: Vec<Tween<f32>>,
 
-    /// The target color for this object to finish by the end of the animation
-    animation_color_target: Vec<Vec2>,
+    /// Animations to start in order on this object once the tracks they target finish
+    animation_queue: Vec<VecDeque<Animation>>,
 
-    /// The target position for this object to finish by the end of the animation
-    animation_position_ease: Vec<EaseFunc>,
+    /// Tracks that finished during the last update
+    finished: Vec<(ObjectIndex, Track)>,
 }
 
 pub struct Object {
     pub position: Vec2,
     pub color: Color,
     pub shape: Shape,
+    pub tag: Tag,
+
+    /// Rotation (in radians) around the pivot
+    pub rotation: f32,
+
+    /// Non-uniform scale around the pivot
+    pub scale: Vec2,
+
+    /// Point to rotate and scale around, relative to the position. The position is the
+    /// top left corner of a rectangle and the center of a circle.
+    pub pivot: Vec2,
 }
 
+impl Default for Object {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            color: WHITE,
+            shape: Shape::Rectangle {
+                width: 1.,
+                height: 1.,
+            },
+            tag: Tag::default(),
+            rotation: 0.,
+            scale: Vec2::ONE,
+            pivot: Vec2::ZERO,
+        }
+    }
+}
+
+/// A snapshot of a live object in the world
 #[derive(Debug, Clone)]
-pub struct ObjectIndex(usize);
+pub struct ObjectRef {
+    pub index: ObjectIndex,
+    pub position: Vec2,
+    pub shape: Shape,
+    pub color: Color,
+    pub tag: Tag,
+    pub rotation: f32,
+    pub scale: Vec2,
+    pub pivot: Vec2,
+    pub animating: bool,
+}
+
+/// Mutable access to a live object in the world
+#[derive(Debug)]
+pub struct ObjectMut<'a> {
+    pub index: ObjectIndex,
+    pub position: &'a mut Vec2,
+    pub shape: &'a mut Shape,
+    pub color: &'a mut Color,
+    pub tag: Tag,
+    pub rotation: &'a mut f32,
+    pub scale: &'a mut Vec2,
+    pub pivot: &'a mut Vec2,
+    pub animating: bool,
+}
+
+/// Handle to an object in the [`World`]
+///
+/// The generation makes handles to a removed object stay dead even after its slot is
+/// reused by a new object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectIndex {
+    /// Slot of the object in the world columns
+    index: usize,
+
+    /// Generation of the slot when the object was pushed
+    generation: u32,
+}
+
+/// Errors returned when operating on world objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldError {
+    /// The object has been removed from the world
+    DeadObject(ObjectIndex),
+
+    /// The animation has no target to animate towards
+    MissingTarget,
+
+    /// An additive animation was given an absolute position
+    AdditiveAbsolute,
+
+    /// An additive animation was given a spring
+    AdditiveSpring,
+
+    /// An additive animation was given smoothing
+    AdditiveSmoothing,
+
+    /// A following animation is additive, was given an absolute position or follows
+    /// the animated object itself
+    InvalidFollow,
+
+    /// The spring of the animation has a non-positive stiffness or mass, or a negative
+    /// damping
+    InvalidSpring,
+}
+
+impl core::fmt::Display for WorldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WorldError::DeadObject(object) => write!(
+                f,
+                "Object {} (generation {}) has been removed",
+                object.index, object.generation
+            ),
+            WorldError::MissingTarget => write!(f, "Unknown target position for animation"),
+            WorldError::AdditiveAbsolute => {
+                write!(f, "Additive animations only accept a relative position")
+            }
+            WorldError::AdditiveSpring => write!(f, "Additive animations can't use a spring"),
+            WorldError::AdditiveSmoothing => {
+                write!(f, "Additive animations can't use smoothing")
+            }
+            WorldError::InvalidSpring => write!(f, "Invalid spring for animation"),
+            WorldError::InvalidFollow => write!(
+                f,
+                "Following animations can't be additive, have an absolute position or \
+                 follow the animated object"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WorldError {}
 
 impl World {
     pub fn push(
@@ -57,124 +231,1357 @@ impl World {
             position,
             shape,
             color,
+            tag,
+            rotation,
+            scale,
+            pivot,
         }: Object,
     ) -> ObjectIndex {
-        // Get the index for this object
-        let index = ObjectIndex(self.positions.len());
-
         let Self {
             positions,
             shapes,
             colors,
+            tags,
+            rotations,
+            scales,
+            pivots,
+            generations,
+            alive,
+            free,
             animating,
-            animation_duration,
-            animation_elapsed,
-            animation_position_start,
-            animation_position_target,
-            animation_color_target,
-            animation_position_ease,
+            position_tracks,
+            follows,
+            rotation_tracks,
+            scale_tracks,
+            color_tracks,
+            color_modes,
+            alpha_tracks,
+            animation_queue,
+            finished: _,
         } = self;
 
+        // Reuse the slot of a removed object if there is one
+        let index = match free.pop() {
+            Some(index) => index,
+            None => {
+                positions.push(Default::default());
+                shapes.push(shape);
+                colors.push(Default::default());
+                tags.push(Default::default());
+                rotations.push(Default::default());
+                scales.push(Default::default());
+                pivots.push(Default::default());
+                generations.push(0);
+                alive.push(false);
+                animating.push(false);
+                position_tracks.push(Default::default());
+                follows.push(None);
+                rotation_tracks.push(Default::default());
+                scale_tracks.push(Default::default());
+                color_tracks.push(Default::default());
+                color_modes.push(Default::default());
+                alpha_tracks.push(Default::default());
+                animation_queue.push(VecDeque::new());
+
+                positions.len() - 1
+            }
+        };
+
         // Add this object to the world
-        positions.push(position);
-        shapes.push(shape);
-        colors.push(color);
-        animating.push(false);
-        animation_duration.push(Default::default());
-        animation_elapsed.push(Default::default());
-        animation_position_start.push(Default::default());
-        animation_position_target.push(Default::default());
-        animation_color_target.push(Default::default());
-        animation_position_ease.push(EaseFunc::Linear);
+        positions[index] = position;
+        shapes[index] = shape;
+        colors[index] = color;
+        tags[index] = tag;
+        rotations[index] = rotation;
+        scales[index] = scale;
+        pivots[index] = pivot;
+        alive[index] = true;
+        animating[index] = false;
+        position_tracks[index] = Default::default();
+        follows[index] = None;
+        rotation_tracks[index] = Default::default();
+        scale_tracks[index] = Default::default();
+        color_tracks[index] = Default::default();
+        color_modes[index] = Default::default();
+        alpha_tracks[index] = Default::default();
+        animation_queue[index].clear();
 
         assert!(positions.len() == shapes.len() && positions.len() == colors.len());
 
-        index
+        ObjectIndex {
+            index,
+            generation: generations[index],
+        }
+    }
+
+    /// Remove the given object from the world, returning its properties
+    pub fn remove(&mut self, object: &ObjectIndex) -> Result<Object, WorldError> {
+        let index = self.slot(object)?;
+
+        // Kill every handle to this slot and make it available for reuse
+        self.alive[index] = false;
+        self.animating[index] = false;
+        self.animation_queue[index].clear();
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(index);
+
+        Ok(Object {
+            position: self.positions[index],
+            color: self.colors[index],
+            shape: self.shapes[index],
+            tag: self.tags[index],
+            rotation: self.rotations[index],
+            scale: self.scales[index],
+            pivot: self.pivots[index],
+        })
+    }
+
+    /// Iterate over every live object in the world
+    pub fn iter(&self) -> impl Iterator<Item = ObjectRef> + '_ {
+        (0..self.positions.len())
+            .filter(|index| self.alive[*index])
+            .map(|index| ObjectRef {
+                index: ObjectIndex {
+                    index,
+                    generation: self.generations[index],
+                },
+                position: self.positions[index],
+                shape: self.shapes[index],
+                color: self.colors[index],
+                tag: self.tags[index],
+                rotation: self.rotations[index],
+                scale: self.scales[index],
+                pivot: self.pivots[index],
+                animating: self.animating[index],
+            })
+    }
+
+    /// Iterate mutably over every live object in the world
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ObjectMut<'_>> {
+        let Self {
+            positions,
+            shapes,
+            colors,
+            tags,
+            rotations,
+            scales,
+            pivots,
+            generations,
+            alive,
+            animating,
+            ..
+        } = self;
+
+        let (tags, generations, alive, animating) = (&*tags, &*generations, &*alive, &*animating);
+
+        positions
+            .iter_mut()
+            .zip(shapes.iter_mut())
+            .zip(colors.iter_mut())
+            .zip(rotations.iter_mut())
+            .zip(scales.iter_mut())
+            .zip(pivots.iter_mut())
+            .enumerate()
+            .filter(|(index, _)| alive[*index])
+            .map(
+                |(index, (((((position, shape), color), rotation), scale), pivot))| ObjectMut {
+                    index: ObjectIndex {
+                        index,
+                        generation: generations[index],
+                    },
+                    position,
+                    shape,
+                    color,
+                    tag: tags[index],
+                    rotation,
+                    scale,
+                    pivot,
+                    animating: animating[index],
+                },
+            )
+    }
+
+    /// Iterate over every live object with the given tag
+    pub fn iter_tag(&self, tag: Tag) -> impl Iterator<Item = ObjectRef> + '_ {
+        self.iter().filter(move |object| object.tag == tag)
+    }
+
+    /// Iterate mutably over every live object with the given tag
+    pub fn iter_tag_mut(&mut self, tag: Tag) -> impl Iterator<Item = ObjectMut<'_>> {
+        self.iter_mut().filter(move |object| object.tag == tag)
+    }
+
+    /// Iterate over every live object with the given kind of shape
+    pub fn iter_shape(&self, kind: ShapeKind) -> impl Iterator<Item = ObjectRef> + '_ {
+        self.iter()
+            .filter(move |object| object.shape.kind() == kind)
+    }
+
+    /// Iterate mutably over every live object with the given kind of shape
+    pub fn iter_shape_mut(&mut self, kind: ShapeKind) -> impl Iterator<Item = ObjectMut<'_>> {
+        self.iter_mut()
+            .filter(move |object| object.shape.kind() == kind)
+    }
+
+    /// Returns `true` if the object has not been removed from the world
+    pub fn is_alive(&self, object: &ObjectIndex) -> bool {
+        self.slot(object).is_ok()
+    }
+
+    /// Number of objects in the world
+    pub fn len(&self) -> usize {
+        self.positions.len() - self.free.len()
+    }
+
+    /// Returns `true` if there are no objects in the world
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the slot of the given object if it is still alive
+    fn slot(&self, object: &ObjectIndex) -> Result<usize, WorldError> {
+        let index = object.index;
+
+        if index < self.positions.len()
+            && self.alive[index]
+            && self.generations[index] == object.generation
+        {
+            Ok(index)
+        } else {
+            Err(WorldError::DeadObject(*object))
+        }
     }
 
     pub fn update(&mut self, frame_time: f32) {
+        self.finished.clear();
+
         for index in 0..self.positions.len() {
             // Only updating the animating objects
             if !self.animating[index] {
                 continue;
             }
 
-            // Get the current progress of this animation
-            let progress = self.animation_elapsed[index] / self.animation_duration[index];
+            // Start the queued animations in order, each once the tracks it targets
+            // have finished
+            while self.animation_queue[index]
+                .front()
+                .is_some_and(|next| !self.is_any_track_active(index, &next.target))
+            {
+                let next = self.animation_queue[index].pop_front().unwrap();
+                self.start(index, next);
+            }
+
+            let object = ObjectIndex {
+                index,
+                generation: self.generations[index],
+            };
+
+            // Move the position target along with the followed object. Objects updated
+            // later in this frame are followed with a frame of delay.
+            if let Some((followed, offset)) = self.follows[index] {
+                match self.slot(&followed) {
+                    Ok(followed) => {
+                        self.position_tracks[index].target = self.positions[followed] + offset;
+                    }
+                    Err(_) => {
+                        // Stop following removed objects
+                        self.follows[index] = None;
+                        if self.position_tracks[index].timing.active {
+                            self.position_tracks[index].timing.active = false;
+                            self.finished.push((object, Track::Position));
+                        }
+                    }
+                }
+            }
+
+            // Update the position, rotation and scale tracks
+            if self.position_tracks[index].apply(&mut self.positions[index], frame_time) {
+                self.finished.push((object, Track::Position));
+            }
 
-            // Apply easing function
-            let progress = self.animation_position_ease[index].calculate(progress);
+            if self.rotation_tracks[index].apply(&mut self.rotations[index], frame_time) {
+                self.finished.push((object, Track::Rotation));
+            }
 
-            // Calculate the new position of this object based on the progress
-            let target = self.animation_position_target[index];
-            let start = self.animation_position_start[index];
-            let new_pos = start + (target - start) * progress;
+            if self.scale_tracks[index].apply(&mut self.scales[index], frame_time) {
+                self.finished.push((object, Track::Scale));
+            }
 
-            // Update the position
-            self.positions[index] = new_pos;
+            // Update the color track, leaving the alpha to its own track
+            let color = self.colors[index];
+            let track = &mut self.color_tracks[index];
+            if let Some(step) = track.advance(color, frame_time) {
+                let new_color =
+                    self.color_modes[index].lerp(track.start, track.target, step.progress);
+                self.colors[index] = Color {
+                    a: color.a,
+                    ..new_color
+                };
 
-            // Stop this animation if the duration has finished
-            self.animation_elapsed[index] += frame_time;
+                if step.finished {
+                    self.finished.push((object, Track::Color));
+                }
+            }
 
-            if self.animation_elapsed[index] >= self.animation_duration[index] {
-                self.animating[index] = false;
+            // Update the alpha track
+            let mut alpha = self.colors[index].a;
+            if self.alpha_tracks[index].apply(&mut alpha, frame_time) {
+                self.finished.push((object, Track::Alpha));
             }
+            self.colors[index].a = alpha.clamp(0., 1.);
+
+            self.animating[index] =
+                self.has_active_track(index) || !self.animation_queue[index].is_empty();
+        }
+    }
+
+    /// Returns `true` if any track of the object at `index` is running or waiting for
+    /// its delay
+    fn has_active_track(&self, index: usize) -> bool {
+        [
+            Track::Position,
+            Track::Rotation,
+            Track::Scale,
+            Track::Color,
+            Track::Alpha,
+        ]
+        .into_iter()
+        .any(|track| self.timing(index, track).active)
+    }
+
+    /// Returns `true` if any of the tracks targeted by `target` of the object at `index`
+    /// is running or waiting for its delay
+    fn is_any_track_active(&self, index: usize, target: &AnimationState) -> bool {
+        target
+            .tracks()
+            .any(|track| self.timing(index, track).active)
+    }
+
+    /// Get the timing of the given track of the object at `index`
+    fn timing(&self, index: usize, track: Track) -> &TweenTiming {
+        match track {
+            Track::Position => &self.position_tracks[index].timing,
+            Track::Rotation => &self.rotation_tracks[index].timing,
+            Track::Scale => &self.scale_tracks[index].timing,
+            Track::Color => &self.color_tracks[index].timing,
+            Track::Alpha => &self.alpha_tracks[index].timing,
+        }
+    }
+
+    /// Get a mut ref to the timing of the given track of the object at `index`
+    fn timing_mut(&mut self, index: usize, track: Track) -> &mut TweenTiming {
+        match track {
+            Track::Position => &mut self.position_tracks[index].timing,
+            Track::Rotation => &mut self.rotation_tracks[index].timing,
+            Track::Scale => &mut self.scale_tracks[index].timing,
+            Track::Color => &mut self.color_tracks[index].timing,
+            Track::Alpha => &mut self.alpha_tracks[index].timing,
         }
     }
 
     // Draw the current world state!
     pub fn draw(&self, macroquad: &Macroquad) {
         for index in 0..self.positions.len() {
+            if !self.alive[index] {
+                continue;
+            }
+
             let shape = &self.shapes[index];
             let position = &self.positions[index];
             let color = self.colors[index];
+            let rotation = self.rotations[index];
+            let scale = self.scales[index];
+            let pivot = self.pivots[index];
 
             match shape {
                 Shape::Rectangle { width, height } => {
-                    (macroquad.draw_rectangle)(position.x, position.y, *width, *height, color);
+                    // The rectangle is drawn at the pivot, offset by the normalized pivot
+                    let offset = Vec2::new(
+                        if *width != 0. { pivot.x / width } else { 0. },
+                        if *height != 0. { pivot.y / height } else { 0. },
+                    );
+
+                    (macroquad.draw_rectangle_ex)(
+                        position.x + pivot.x,
+                        position.y + pivot.y,
+                        width * scale.x,
+                        height * scale.y,
+                        DrawRectangleParams {
+                            offset,
+                            rotation,
+                            color,
+                        },
+                    );
                 }
                 Shape::Circle { radius } => {
-                    (macroquad.draw_circle)(position.x, position.y, *radius, RED);
+                    let center = self.transform(index, Vec2::ZERO);
+
+                    if scale.x == scale.y {
+                        (macroquad.draw_circle)(center.x, center.y, radius * scale.x, color);
+                    } else {
+                        (macroquad.draw_ellipse)(
+                            center.x,
+                            center.y,
+                            radius * scale.x,
+                            radius * scale.y,
+                            rotation.to_degrees(),
+                            color,
+                        );
+                    }
                 }
             }
         }
     }
 
-    pub fn animate(&mut self, object: &ObjectIndex, animation: Animation) {
-        let Animation {
-            target,
-            ease,
-            duration,
-        } = animation;
+    /// Transform a point relative to the position of the object at `index` into world
+    /// coordinates, applying the scale and rotation around the pivot
+    fn transform(&self, index: usize, point: Vec2) -> Vec2 {
+        let pivot = self.pivots[index];
+        let local = (point - pivot) * self.scales[index];
+
+        self.positions[index] + pivot + Vec2::from_angle(self.rotations[index]).rotate(local)
+    }
+
+    /// Get the axis aligned bounds of the given object, accounting for its rotation
+    /// and scale
+    pub fn bounds(&self, object: &ObjectIndex) -> Option<Rect> {
+        let index = self.slot(object).ok()?;
+
+        match self.shapes[index] {
+            Shape::Rectangle { width, height } => {
+                let corners = [
+                    Vec2::new(0., 0.),
+                    Vec2::new(width, 0.),
+                    Vec2::new(0., height),
+                    Vec2::new(width, height),
+                ]
+                .map(|corner| self.transform(index, corner));
+
+                let min = corners.into_iter().reduce(Vec2::min).unwrap();
+                let max = corners.into_iter().reduce(Vec2::max).unwrap();
 
-        match target {
-            AnimationState {
-                absolute_position: Some(target),
-                ..
-            } => {
-                self.animation_position_target[object.0] = target;
-                self.animation_position_start[object.0] = self.position(object);
+                Some(Rect::new(min.x, min.y, max.x - min.x, max.y - min.y))
+            }
+            Shape::Circle { radius } => {
+                // Half extents of the rotated ellipse
+                let center = self.transform(index, Vec2::ZERO);
+                let axes = self.scales[index].abs() * radius;
+                let (sin, cos) = self.rotations[index].sin_cos();
+                let half = Vec2::new(
+                    ((axes.x * cos).powi(2) + (axes.y * sin).powi(2)).sqrt(),
+                    ((axes.x * sin).powi(2) + (axes.y * cos).powi(2)).sqrt(),
+                );
+
+                Some(Rect::new(
+                    center.x - half.x,
+                    center.y - half.y,
+                    half.x * 2.,
+                    half.y * 2.,
+                ))
             }
-            _ => panic!("Unknown target position for animation"),
         }
+    }
 
-        self.animation_position_ease[object.0] = ease;
-        self.animation_duration[object.0] = duration;
-        self.animating[object.0] = true;
+    /// Get the collision shape of the given object in world coordinates. Rectangles
+    /// collide with their axis aligned bounds and scaled circles with a circle around
+    /// their larger axis.
+    pub fn collider(&self, object: &ObjectIndex) -> Option<Collider> {
+        let index = self.slot(object).ok()?;
+
+        match self.shapes[index] {
+            Shape::Rectangle { .. } => Some(Collider::Rect(self.bounds(object)?)),
+            Shape::Circle { radius } => Some(Collider::Circle {
+                center: self.transform(index, Vec2::ZERO),
+                radius: radius * self.scales[index].abs().max_element(),
+            }),
+        }
+    }
+
+    /// Get the contact of object `a` with object `b`, or `None` if either is dead or
+    /// they don't overlap. The normal points from `b` towards `a`.
+    pub fn contact(&self, a: &ObjectIndex, b: &ObjectIndex) -> Option<Contact> {
+        self.collider(a)?.contact(&self.collider(b)?)
+    }
+
+    /// Iterate over every other object overlapping the given object, with the contact
+    /// pointing towards the given object
+    pub fn contacts(
+        &self,
+        object: &ObjectIndex,
+    ) -> impl Iterator<Item = (ObjectIndex, Contact)> + '_ {
+        let collider = self.collider(object);
+        let object = *object;
+
+        self.iter()
+            .filter(move |other| other.index != object)
+            .filter_map(move |other| {
+                let contact = collider?.contact(&self.collider(&other.index)?)?;
+                Some((other.index, contact))
+            })
+    }
+
+    /// Get the first object the given object hits when moved by `motion`, considering
+    /// only the objects `solid` returns `true` for
+    pub fn sweep(
+        &self,
+        object: &ObjectIndex,
+        motion: Vec2,
+        mut solid: impl FnMut(&ObjectRef) -> bool,
+    ) -> Option<(ObjectIndex, Hit)> {
+        let collider = self.collider(object)?;
+
+        self.iter()
+            .filter(|other| other.index != *object && solid(other))
+            .filter_map(|other| {
+                let hit = collider.sweep(motion, &self.collider(&other.index)?)?;
+                Some((other.index, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time))
+    }
+
+    /// Move the given object by `velocity` over `frame_time`, bouncing off every object
+    /// `solid` returns `true` for along the way. Returns the hits in the order they
+    /// happened.
+    pub fn move_and_bounce(
+        &mut self,
+        object: &ObjectIndex,
+        velocity: &mut Vec2,
+        frame_time: f32,
+        mut solid: impl FnMut(&ObjectRef) -> bool,
+    ) -> Vec<(ObjectIndex, Hit)> {
+        /// Most bounces resolved in a single move, so getting wedged can't hang the game
+        const MAX_BOUNCES: usize = 8;
+
+        /// Distance kept from a surface after hitting it
+        const SKIN: f32 = 1e-4;
+
+        let mut hits = Vec::new();
+        let mut motion = *velocity * frame_time;
+
+        for _ in 0..MAX_BOUNCES {
+            let hit = self.sweep(object, motion, &mut solid);
+
+            let Some(position) = self.position_mut(object) else {
+                break;
+            };
+
+            let Some((other, hit)) = hit else {
+                *position += motion;
+                break;
+            };
+
+            // Move up to the surface and reflect the rest of the motion off of it
+            *position += motion * hit.time + hit.normal * SKIN;
+            motion *= 1. - hit.time;
+
+            let reflect = |value: Vec2| value - 2. * value.dot(hit.normal) * hit.normal;
+            motion = reflect(motion);
+            *velocity = reflect(*velocity);
+
+            hits.push((other, hit));
+        }
+
+        hits
+    }
+
+    /// Start animating the given object after the animation delay. Only the tracks
+    /// targeted by the animation are replaced, the other tracks keep running.
+    pub fn animate(
+        &mut self,
+        object: &ObjectIndex,
+        animation: Animation,
+    ) -> Result<(), WorldError> {
+        let index = self.slot(object)?;
+        self.check(index, &animation)?;
+
+        self.start(index, animation);
+        self.animating[index] = true;
+
+        Ok(())
+    }
+
+    /// Animate the given object once the already queued animations have started and
+    /// the tracks targeted by this animation have finished. Other tracks may keep
+    /// running. The delay of the animation starts counting from that point.
+    pub fn queue(&mut self, object: &ObjectIndex, animation: Animation) -> Result<(), WorldError> {
+        let index = self.slot(object)?;
+        self.check(index, &animation)?;
+
+        if self.is_any_track_active(index, &animation.target)
+            || !self.animation_queue[index].is_empty()
+        {
+            self.animation_queue[index].push_back(animation);
+        } else {
+            self.start(index, animation);
+        }
+
+        self.animating[index] = true;
+
+        Ok(())
+    }
+
+    /// Stop the given track of an object, leaving the property where it currently is
+    pub fn cancel(&mut self, object: &ObjectIndex, track: Track) -> Result<(), WorldError> {
+        let index = self.slot(object)?;
+        self.timing_mut(index, track).active = false;
+
+        if track == Track::Position {
+            self.follows[index] = None;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the given track of an object is running or waiting for its
+    /// delay
+    pub fn is_track_animating(&self, object: &ObjectIndex, track: Track) -> bool {
+        self.track_progress(object, track).is_some()
+    }
+
+    /// Get the linear progress (`0..=1`) of the given track of an object, or `None` if
+    /// the track is not animating
+    pub fn track_progress(&self, object: &ObjectIndex, track: Track) -> Option<f32> {
+        let index = self.slot(object).ok()?;
+        self.timing(index, track).linear_progress()
+    }
+
+    /// Tracks that finished during the last [`World::update`]. An object with a
+    /// sequence of animations is reported once per finished track of each animation.
+    pub fn finished_animations(&self) -> &[(ObjectIndex, Track)] {
+        &self.finished
+    }
+
+    /// Make sure the animation can be started on the object at `index`
+    fn check(&self, index: usize, animation: &Animation) -> Result<(), WorldError> {
+        let AnimationState {
+            absolute_position,
+            relative_position,
+            rotation,
+            scale,
+            color,
+            alpha,
+            follow,
+            additive,
+            ..
+        } = &animation.target;
+
+        if let Some(followed) = follow {
+            if *additive || absolute_position.is_some() {
+                return Err(WorldError::InvalidFollow);
+            }
+
+            if self.slot(followed)? == index {
+                return Err(WorldError::InvalidFollow);
+            }
+        }
+
+        if *additive && absolute_position.is_some() {
+            return Err(WorldError::AdditiveAbsolute);
+        }
+
+        if let Some(spring) = &animation.spring {
+            if *additive {
+                return Err(WorldError::AdditiveSpring);
+            }
+
+            if !spring.is_valid() {
+                return Err(WorldError::InvalidSpring);
+            }
+        }
+
+        if *additive && animation.smoothing.is_some() {
+            return Err(WorldError::AdditiveSmoothing);
+        }
+
+        if absolute_position.is_none()
+            && relative_position.is_none()
+            && rotation.is_none()
+            && scale.is_none()
+            && color.is_none()
+            && alpha.is_none()
+            && follow.is_none()
+        {
+            return Err(WorldError::MissingTarget);
+        }
+
+        Ok(())
+    }
+
+    /// Start the tracks targeted by the checked `animation` on the object at `index`
+    fn start(&mut self, index: usize, animation: Animation) {
+        let color_ease = animation
+            .target
+            .color_ease
+            .as_ref()
+            .unwrap_or(&animation.ease);
+        let timing = TweenTiming::new(&animation, &animation.ease).with_motion(&animation);
+        let color_timing = TweenTiming::new(&animation, color_ease);
+
+        let AnimationState {
+            absolute_position,
+            relative_position,
+            rotation,
+            scale,
+            color,
+            color_mode,
+            alpha,
+            follow,
+            ..
+        } = animation.target;
+
+        // An absolute position is final, a relative one is resolved when the track starts
+        // and a followed one every frame
+        match (absolute_position, relative_position, follow) {
+            (None, None, None) => {}
+            (_, offset, Some(followed)) => {
+                let offset = offset.unwrap_or_default();
+                let target = self.position(&followed).unwrap_or_default() + offset;
+                self.follows[index] = Some((followed, offset));
+                self.position_tracks[index].retarget(target, timing.clone().moving_target());
+            }
+            (Some(absolute), relative, None) => {
+                self.follows[index] = None;
+                self.position_tracks[index]
+                    .retarget(absolute + relative.unwrap_or_default(), timing.clone());
+            }
+            (None, Some(relative), None) => {
+                self.follows[index] = None;
+                self.position_tracks[index].retarget(relative, timing.clone().relative());
+            }
+        }
+
+        if let Some(rotation) = rotation {
+            self.rotation_tracks[index].retarget(rotation, timing.clone());
+        }
+
+        if let Some(scale) = scale {
+            self.scale_tracks[index].retarget(scale, timing);
+        }
+
+        if let Some(color) = color {
+            self.color_tracks[index] = Tween::new(color, color_timing.clone());
+            self.color_modes[index] = color_mode;
+        }
+
+        if let Some(alpha) = alpha {
+            self.alpha_tracks[index] = Tween::new(alpha, color_timing);
+        }
     }
 
     /// Returns `true` if this object is animating and `false` otherwise
     pub fn is_animating(&self, object: &ObjectIndex) -> bool {
-        self.animating[object.0]
+        self.slot(object).is_ok_and(|index| self.animating[index])
     }
 
     /// Get the position of the given object
-    pub fn position(&self, object: &ObjectIndex) -> Vec2 {
-        self.positions[object.0]
+    pub fn position(&self, object: &ObjectIndex) -> Option<Vec2> {
+        Some(self.positions[self.slot(object).ok()?])
     }
 
     /// Get a mut ref to the position of the given object
-    pub fn position_mut(&mut self, object: &ObjectIndex) -> &mut Vec2 {
-        &mut self.positions[object.0]
+    pub fn position_mut(&mut self, object: &ObjectIndex) -> Option<&mut Vec2> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.positions[index])
+    }
+
+    /// Get the shape of the given object
+    pub fn shape(&self, object: &ObjectIndex) -> Option<Shape> {
+        Some(self.shapes[self.slot(object).ok()?])
+    }
+
+    /// Get the tag of the given object
+    pub fn tag(&self, object: &ObjectIndex) -> Option<Tag> {
+        Some(self.tags[self.slot(object).ok()?])
+    }
+
+    /// Get a mut ref to the tag of the given object
+    pub fn tag_mut(&mut self, object: &ObjectIndex) -> Option<&mut Tag> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.tags[index])
+    }
+
+    /// Get the color of the given object
+    pub fn color(&self, object: &ObjectIndex) -> Option<Color> {
+        Some(self.colors[self.slot(object).ok()?])
+    }
+
+    /// Get a mut ref to the color of the given object
+    pub fn color_mut(&mut self, object: &ObjectIndex) -> Option<&mut Color> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.colors[index])
+    }
+
+    /// Get the rotation (in radians) of the given object
+    pub fn rotation(&self, object: &ObjectIndex) -> Option<f32> {
+        Some(self.rotations[self.slot(object).ok()?])
+    }
+
+    /// Get a mut ref to the rotation (in radians) of the given object
+    pub fn rotation_mut(&mut self, object: &ObjectIndex) -> Option<&mut f32> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.rotations[index])
+    }
+
+    /// Get the scale of the given object
+    pub fn scale(&self, object: &ObjectIndex) -> Option<Vec2> {
+        Some(self.scales[self.slot(object).ok()?])
+    }
+
+    /// Get a mut ref to the scale of the given object
+    pub fn scale_mut(&mut self, object: &ObjectIndex) -> Option<&mut Vec2> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.scales[index])
+    }
+
+    /// Get the pivot of the given object
+    pub fn pivot(&self, object: &ObjectIndex) -> Option<Vec2> {
+        Some(self.pivots[self.slot(object).ok()?])
+    }
+
+    /// Get a mut ref to the pivot of the given object
+    pub fn pivot_mut(&mut self, object: &ObjectIndex) -> Option<&mut Vec2> {
+        let index = self.slot(object).ok()?;
+        Some(&mut self.pivots[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seconds per frame used to step the world
+    const FRAME: f32 = 1. / 60.;
+
+    /// A world with a single default object
+    fn world_with_object() -> (World, ObjectIndex) {
+        let mut world = World::default();
+        let object = world.push(Object::default());
+        (world, object)
+    }
+
+    /// Step the world forward by `seconds`
+    fn run(world: &mut World, seconds: f32) {
+        for _ in 0..(seconds / FRAME).round() as usize {
+            world.update(FRAME);
+        }
+    }
+
+    /// A linear animation to the absolute `position`
+    fn move_to(position: Vec2, duration: f32) -> Animation {
+        Animation {
+            target: AnimationState {
+                absolute_position: Some(position),
+                ..Default::default()
+            },
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn removed_slot_is_reused() {
+        let mut world = World::default();
+        let first = world.push(Object::default());
+        let second = world.push(Object::default());
+
+        let removed = world.remove(&first).expect("Object is alive");
+        assert_eq!(removed.position, Vec2::ZERO);
+        assert_eq!(world.len(), 1);
+
+        // The new object takes the free slot instead of growing the columns
+        let third = world.push(Object {
+            position: Vec2::ONE,
+            ..Default::default()
+        });
+        assert_eq!(third.index, first.index);
+        assert_ne!(third, first);
+        assert_eq!(world.positions.len(), 2);
+        assert!(world.free.is_empty());
+
+        assert_eq!(world.position(&third), Some(Vec2::ONE));
+        assert!(world.is_alive(&second));
+    }
+
+    #[test]
+    fn stale_index_is_rejected() {
+        let mut world = World::default();
+        let stale = world.push(Object::default());
+        world.remove(&stale).unwrap();
+        let _reused = world.push(Object::default());
+
+        assert!(!world.is_alive(&stale));
+        assert_eq!(world.position(&stale), None);
+        assert!(world.position_mut(&stale).is_none());
+        assert_eq!(
+            world.remove(&stale).err(),
+            Some(WorldError::DeadObject(stale))
+        );
+        assert_eq!(
+            world.animate(&stale, move_to(Vec2::ONE, 1.)).err(),
+            Some(WorldError::DeadObject(stale))
+        );
+    }
+
+    #[test]
+    fn free_list_reuses_latest_removal_first() {
+        let mut world = World::default();
+        let objects: Vec<ObjectIndex> = (0..3).map(|_| world.push(Object::default())).collect();
+
+        world.remove(&objects[0]).unwrap();
+        world.remove(&objects[2]).unwrap();
+        assert_eq!(world.free, [0, 2]);
+        assert_eq!(world.len(), 1);
+
+        assert_eq!(world.push(Object::default()).index, 2);
+        assert_eq!(world.push(Object::default()).index, 0);
+        assert_eq!(world.push(Object::default()).index, 3);
+        assert_eq!(world.len(), 4);
+    }
+
+    /// A world with a circle and a rectangle tagged 1 and a rectangle tagged 2
+    fn mixed_world() -> (World, [ObjectIndex; 3]) {
+        let mut world = World::default();
+        let circle = world.push(Object {
+            shape: Shape::Circle { radius: 0.5 },
+            tag: Tag(1),
+            ..Default::default()
+        });
+        let block = world.push(Object {
+            tag: Tag(1),
+            ..Default::default()
+        });
+        let paddle = world.push(Object {
+            tag: Tag(2),
+            ..Default::default()
+        });
+
+        (world, [circle, block, paddle])
+    }
+
+    #[test]
+    fn filters_by_shape_and_tag() {
+        let (mut world, [circle, block, paddle]) = mixed_world();
+
+        let indices = |objects: Vec<ObjectRef>| -> Vec<ObjectIndex> {
+            objects.into_iter().map(|object| object.index).collect()
+        };
+
+        assert_eq!(
+            indices(world.iter_shape(ShapeKind::Circle).collect()),
+            [circle]
+        );
+        assert_eq!(
+            indices(world.iter_shape(ShapeKind::Rectangle).collect()),
+            [block, paddle]
+        );
+        assert_eq!(indices(world.iter_tag(Tag(1)).collect()), [circle, block]);
+        assert!(world.iter_tag(Tag(3)).next().is_none());
+
+        // Removed objects are skipped
+        world.remove(&block).unwrap();
+        assert_eq!(indices(world.iter_tag(Tag(1)).collect()), [circle]);
+        assert_eq!(world.iter().count(), 2);
+    }
+
+    #[test]
+    fn mutable_iterators_write_back() {
+        let (mut world, [circle, block, paddle]) = mixed_world();
+
+        for object in world.iter_tag_mut(Tag(1)) {
+            *object.position += Vec2::X;
+        }
+
+        for object in world.iter_shape_mut(ShapeKind::Rectangle) {
+            *object.color = RED;
+        }
+
+        for object in world.iter_mut().filter(|object| object.index == paddle) {
+            *object.rotation = 1.;
+            *object.scale = Vec2::splat(2.);
+        }
+
+        assert_eq!(world.position(&circle), Some(Vec2::X));
+        assert_eq!(world.position(&block), Some(Vec2::X));
+        assert_eq!(world.position(&paddle), Some(Vec2::ZERO));
+        assert_eq!(world.color(&circle), Some(WHITE));
+        assert_eq!(world.color(&block), Some(RED));
+        assert_eq!(world.color(&paddle), Some(RED));
+        assert_eq!(world.rotation(&paddle), Some(1.));
+        assert_eq!(world.scale(&paddle), Some(Vec2::splat(2.)));
+    }
+
+    #[test]
+    fn rotated_rectangle_collides_with_its_bounds() {
+        let mut world = World::default();
+        let block = world.push(Object {
+            shape: Shape::Rectangle {
+                width: 2.,
+                height: 1.,
+            },
+            rotation: core::f32::consts::FRAC_PI_2,
+            pivot: Vec2::new(1., 0.5),
+            ..Default::default()
+        });
+
+        // A quarter turn around the center swaps the width and height
+        let Some(Collider::Rect(rect)) = world.collider(&block) else {
+            panic!("Rectangles collide as a rect");
+        };
+        assert!((rect.x - 0.5).abs() < 1e-5);
+        assert!((rect.y + 0.5).abs() < 1e-5);
+        assert!((rect.w - 1.).abs() < 1e-5);
+        assert!((rect.h - 2.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn scaled_circle_collides_with_its_larger_axis() {
+        let mut world = World::default();
+        let ball = world.push(Object {
+            position: Vec2::new(3., 3.),
+            shape: Shape::Circle { radius: 1. },
+            scale: Vec2::new(0.5, -2.),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            world.collider(&ball),
+            Some(Collider::Circle {
+                center: Vec2::new(3., 3.),
+                radius: 2.,
+            })
+        );
+    }
+
+    /// A linear animation to `color`
+    fn fade_to(color: Color, duration: f32) -> Animation {
+        Animation {
+            target: AnimationState {
+                color: Some(color),
+                ..Default::default()
+            },
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn animating_one_property_keeps_other_tracks() {
+        let (mut world, object) = world_with_object();
+
+        world.animate(&object, move_to(Vec2::X, 1.)).unwrap();
+        run(&mut world, 0.5);
+
+        // Starting a color animation doesn't restart or stop the position track
+        world.animate(&object, fade_to(BLACK, 1.)).unwrap();
+        let progress = world.track_progress(&object, Track::Position).unwrap();
+        assert!((progress - 0.5).abs() < 0.02);
+
+        run(&mut world, 0.55);
+        assert_eq!(world.position(&object), Some(Vec2::X));
+        assert!(!world.is_track_animating(&object, Track::Position));
+        assert!(world.is_track_animating(&object, Track::Color));
+
+        run(&mut world, 0.5);
+        assert_eq!(world.color(&object).map(|color| color.r), Some(0.));
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn cancel_stops_a_single_track() {
+        let (mut world, object) = world_with_object();
+
+        world.animate(&object, move_to(Vec2::X, 1.)).unwrap();
+        world.animate(&object, fade_to(BLACK, 1.)).unwrap();
+        run(&mut world, 0.5);
+
+        world.cancel(&object, Track::Color).unwrap();
+        let color = world.color(&object).unwrap();
+        assert!(color.r > 0. && color.r < 1.);
+
+        // The color stays where it was cancelled while the position keeps moving
+        run(&mut world, 0.55);
+        assert_eq!(world.color(&object), Some(color));
+        assert_eq!(world.position(&object), Some(Vec2::X));
+    }
+
+    /// A linear animation moving by `offset`
+    fn move_by(offset: Vec2, duration: f32, additive: bool) -> Animation {
+        Animation {
+            target: AnimationState {
+                relative_position: Some(offset),
+                additive,
+                ..Default::default()
+            },
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn relative_target_resolves_after_delay() {
+        let (mut world, object) = world_with_object();
+
+        let animation = Animation {
+            delay: 0.5,
+            ..move_by(Vec2::X, 0.5, false)
+        };
+        world.animate(&object, animation).unwrap();
+
+        // Moving the object during the delay moves where the animation starts from
+        run(&mut world, 0.25);
+        *world.position_mut(&object).unwrap() = Vec2::new(5., 0.);
+
+        run(&mut world, 1.);
+        assert_eq!(world.position(&object), Some(Vec2::new(6., 0.)));
+    }
+
+    #[test]
+    fn additive_animations_stack() {
+        let (mut world, object) = world_with_object();
+
+        world.animate(&object, move_by(Vec2::X, 1., true)).unwrap();
+        run(&mut world, 0.5);
+        world.animate(&object, move_by(Vec2::Y, 1., true)).unwrap();
+        run(&mut world, 0.5);
+
+        // Gameplay movement in the middle of both animations is kept
+        *world.position_mut(&object).unwrap() += Vec2::new(0., 2.);
+
+        run(&mut world, 1.);
+        let position = world.position(&object).unwrap();
+        assert!(position.distance(Vec2::new(1., 3.)) < 1e-4);
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn additive_motion_is_rejected() {
+        let (mut world, object) = world_with_object();
+
+        let spring = Animation {
+            spring: Some(Spring::default()),
+            ..move_by(Vec2::X, 1., true)
+        };
+        assert_eq!(
+            world.animate(&object, spring),
+            Err(WorldError::AdditiveSpring)
+        );
+
+        let smoothed = Animation {
+            smoothing: Some(20.),
+            ..move_by(Vec2::X, 1., true)
+        };
+        assert_eq!(
+            world.animate(&object, smoothed),
+            Err(WorldError::AdditiveSmoothing)
+        );
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn additive_yoyo_returns_the_offset() {
+        let (mut world, object) = world_with_object();
+        let start = world.position(&object).unwrap();
+
+        let bump = Animation {
+            repeat: Repeat::Times(2),
+            yoyo: true,
+            ease: EaseFunc::SmoothStop2,
+            ..move_by(Vec2::Y, 0.1, true)
+        };
+        world.animate(&object, bump).unwrap();
+
+        // Steering in the middle of the bump is kept
+        run(&mut world, 0.1);
+        *world.position_mut(&object).unwrap() += Vec2::X;
+
+        run(&mut world, 0.2);
+        let position = world.position(&object).unwrap();
+        assert!(position.distance(start + Vec2::X) < 1e-4);
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn queued_animations_play_in_order() {
+        let (mut world, object) = world_with_object();
+
+        // Nothing is running, so the first queued animation starts right away
+        world.queue(&object, move_to(Vec2::X, 0.5)).unwrap();
+        world.queue(&object, move_to(Vec2::Y, 0.5)).unwrap();
+        assert!(world.is_track_animating(&object, Track::Position));
+        assert_eq!(world.track_progress(&object, Track::Position), Some(0.));
+
+        // Each animation reports its track once when it finishes
+        let mut finished = Vec::new();
+        for _ in 0..70 {
+            world.update(FRAME);
+            finished.extend_from_slice(world.finished_animations());
+        }
+
+        assert_eq!(finished, [(object, Track::Position); 2]);
+        assert_eq!(world.position(&object), Some(Vec2::Y));
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn queries_report_idle_and_dead_tracks() {
+        let (mut world, object) = world_with_object();
+        assert!(!world.is_track_animating(&object, Track::Position));
+        assert_eq!(world.track_progress(&object, Track::Position), None);
+
+        world.animate(&object, move_to(Vec2::X, 1.)).unwrap();
+        world.cancel(&object, Track::Position).unwrap();
+        assert_eq!(world.track_progress(&object, Track::Position), None);
+
+        world.queue(&object, move_to(Vec2::Y, 1.)).unwrap();
+        world.remove(&object).unwrap();
+        assert!(!world.is_track_animating(&object, Track::Position));
+        assert_eq!(
+            world.cancel(&object, Track::Position),
+            Err(WorldError::DeadObject(object))
+        );
+        assert_eq!(
+            world.queue(&object, move_to(Vec2::Y, 1.)),
+            Err(WorldError::DeadObject(object))
+        );
+
+        // The queue of the removed object doesn't carry over to the reused slot
+        let reused = world.push(Object::default());
+        run(&mut world, 0.1);
+        assert!(!world.is_animating(&reused));
+    }
+
+    /// An animation following `followed` at `offset` with exponential smoothing
+    fn follow(followed: ObjectIndex, offset: Vec2) -> Animation {
+        Animation {
+            target: AnimationState {
+                relative_position: Some(offset),
+                follow: Some(followed),
+                ..Default::default()
+            },
+            smoothing: Some(20.),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn following_itself_is_rejected() {
+        let (mut world, object) = world_with_object();
+
+        assert_eq!(
+            world.animate(&object, follow(object, Vec2::X)),
+            Err(WorldError::InvalidFollow)
+        );
+        assert_eq!(
+            world.queue(&object, follow(object, Vec2::X)),
+            Err(WorldError::InvalidFollow)
+        );
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn follower_tracks_moving_object() {
+        let (mut world, leader) = world_with_object();
+        let follower = world.push(Object::default());
+        world.animate(&follower, follow(leader, Vec2::Y)).unwrap();
+
+        // Following keeps running after catching up with the object
+        run(&mut world, 1.);
+        assert!(world.is_track_animating(&follower, Track::Position));
+        assert!(world.position(&follower).unwrap().distance(Vec2::Y) < 1e-3);
+
+        *world.position_mut(&leader).unwrap() = Vec2::new(4., 0.);
+        run(&mut world, 1.);
+        let target = Vec2::new(4., 1.);
+        assert!(world.position(&follower).unwrap().distance(target) < 1e-3);
+
+        // Removing the followed object stops the track where it is
+        world.remove(&leader).unwrap();
+        world.update(FRAME);
+        assert!(!world.is_track_animating(&follower, Track::Position));
+        assert!(world
+            .finished_animations()
+            .contains(&(follower, Track::Position)));
+    }
+
+    #[test]
+    fn smoothing_decays_independent_of_frame_rate() {
+        let rate = 3.;
+        let smooth = Animation {
+            smoothing: Some(rate),
+            ..move_to(Vec2::X, 0.)
+        };
+
+        // The remaining distance after half a second is the same at any frame rate
+        for frames in [5, 30, 240] {
+            let (mut world, object) = world_with_object();
+            world.animate(&object, smooth.clone()).unwrap();
+
+            for _ in 0..frames {
+                world.update(0.5 / frames as f32);
+            }
+
+            let remaining = 1. - world.position(&object).unwrap().x;
+            assert!((remaining - (-rate * 0.5_f32).exp()).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn springs_drive_scale_and_rotation() {
+        let (mut world, object) = world_with_object();
+
+        let wobble = Animation {
+            target: AnimationState {
+                scale: Some(Vec2::new(2., 0.5)),
+                rotation: Some(1.),
+                ..Default::default()
+            },
+            spring: Some(Spring::default()),
+            ..Default::default()
+        };
+        world.animate(&object, wobble).unwrap();
+
+        let mut finished = Vec::new();
+        for _ in 0..120 {
+            world.update(FRAME);
+            finished.extend_from_slice(world.finished_animations());
+        }
+
+        assert_eq!(world.scale(&object), Some(Vec2::new(2., 0.5)));
+        assert_eq!(world.rotation(&object), Some(1.));
+        assert!(finished.contains(&(object, Track::Scale)));
+        assert!(finished.contains(&(object, Track::Rotation)));
+        assert!(!world.is_animating(&object));
+    }
+
+    #[test]
+    fn queue_only_waits_for_targeted_tracks() {
+        let (mut world, object) = world_with_object();
+
+        // A slow spring on the scale
+        let squash = Animation {
+            target: AnimationState {
+                scale: Some(Vec2::new(2., 2.)),
+                ..Default::default()
+            },
+            spring: Some(Spring {
+                damping: 1.,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        world.animate(&object, squash).unwrap();
+        world.animate(&object, move_to(Vec2::X, 0.1)).unwrap();
+        world.queue(&object, move_to(Vec2::ZERO, 0.1)).unwrap();
+
+        // The queued move starts right after the first one, while the spring still runs
+        run(&mut world, 0.15);
+        assert!(world.is_track_animating(&object, Track::Scale));
+        assert!(world.is_track_animating(&object, Track::Position));
+        assert!(world.position(&object).unwrap().x < 1.);
+
+        run(&mut world, 0.1);
+        assert!(world.is_track_animating(&object, Track::Scale));
+        assert!(!world.is_track_animating(&object, Track::Position));
+        assert_eq!(world.position(&object), Some(Vec2::ZERO));
     }
 }
@@ -20,7 +20,7 @@ extern "C" {
 pub const RTLD_LAZY: u32 = 1;
 
 /// The library game logic to query for hot reload
-pub const LIBGAME: &'static str = "./game/target/release/libgame.so";
+pub const LIBGAME: &str = "./game/target/release/libgame.so";
 
 /// Handle to an opened shared library
 #[repr(transparent)]
@@ -1,8 +1,5 @@
 #![feature(stmt_expr_attributes)]
 
-use std::io::Write;
-use std::mem::size_of;
-
 use game_context::*;
 use macroquad::prelude::*;
 
@@ -42,34 +39,6 @@ impl LoopData {
         self.next_index = (self.next_index + 1) % self.buttons.len();
         self.buttons[index].clone()
     }
-
-    pub fn write_to_disk(&self, filename: &str) {
-        if self.game_state.is_none() {
-            eprintln!("ERROR: No state found in LoopData.. ");
-            return;
-        }
-
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .open(filename)
-            .expect("Failed to open loop state file");
-
-        let bytes = unsafe {
-            std::slice::from_raw_parts(
-                (self.game_state.as_ref().unwrap() as *const State) as *const u8,
-                size_of::<State>(),
-            )
-        };
-        file.write(&bytes).unwrap();
-
-        // Write the memory to the save file
-        // [len u64][bytes [u8; len]]
-        assert!(self.memory.len() == MEMORY_LENGTH);
-        let memory_len = self.memory.len() as u64;
-        file.write(&memory_len.to_le_bytes()).unwrap();
-        file.write(self.memory.as_slice()).unwrap();
-    }
 }
 
 #[macroquad::main("Juicing example")]
@@ -83,12 +52,34 @@ async fn main() {
     // Get the macroquad functions
     let macroquad = game_context::get_macroquad();
 
-    let mut memory = Memory::new();
+    // Map the game memory as requested on the command line
+    let memory_config = match MemoryConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => MemoryConfig {
+            schema: MEMORY_SCHEMA,
+            ..config
+        },
+        Err(err) => {
+            eprintln!("ERROR: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut memory = Memory::with_config(&memory_config).unwrap_or_else(|err| {
+        eprintln!("ERROR: {err}");
+        std::process::exit(1);
+    });
     let mut game = None;
+    let mut show_memory_overlay = false;
+    let mut show_ease_visualizer = false;
+
+    // Snapshot of the memory and the game state (which lives outside of the memory) to
+    // diff against for the diff overlay
+    let mut snapshot = memory.data_as_vec();
+    let mut snapshot_state: Option<State> = None;
+    let mut show_diff_overlay = false;
+    let diff_registry = DiffRegistry::default();
     let frame_time = get_frame_time();
 
-    let mut buttons = Vec::new();
-
     // One-time setup of the camera
     setup_camera();
 
@@ -96,9 +87,16 @@ async fn main() {
     loop {
         // Check if the game library has been updated
         // If so, reload the main game function
+        let created_time = game_code.created_time;
         game_code = game_code.reload();
         game_update_and_render = &game_code.game_update_and_render;
 
+        // Diff against the memory from the moment of the hot reload
+        if game_code.created_time != created_time {
+            snapshot = memory.data_as_vec();
+            snapshot_state = game.clone();
+        }
+
         #[cfg(target_os = "linux")]
         if is_key_released(KeyCode::L) {
             match loop_state {
@@ -112,6 +110,10 @@ async fn main() {
                         next_index: 0,
                     };
 
+                    // Diff against the memory from the start of the loop
+                    snapshot = loop_data.memory.clone();
+                    snapshot_state = loop_data.game_state.clone();
+
                     loop_state = LoopState::Record;
                 }
                 LoopState::Record => {
@@ -125,7 +127,28 @@ async fn main() {
             }
         }
 
-        buttons = get_keys_down().iter().cloned().collect();
+        // M - toggle the memory usage overlay
+        if is_key_released(KeyCode::M) {
+            show_memory_overlay = !show_memory_overlay;
+        }
+
+        // N - toggle the memory diff overlay
+        if is_key_released(KeyCode::N) {
+            show_diff_overlay = !show_diff_overlay;
+        }
+
+        // V - toggle the ease visualizer
+        if is_key_released(KeyCode::V) {
+            show_ease_visualizer = !show_ease_visualizer;
+        }
+
+        // K - take a new snapshot for the memory diff overlay
+        if is_key_released(KeyCode::K) {
+            snapshot = memory.data_as_vec();
+            snapshot_state = game.clone();
+        }
+
+        let mut buttons: Vec<KeyCode> = get_keys_down().iter().cloned().collect();
 
         #[cfg(target_os = "linux")]
         match loop_state {
@@ -142,13 +165,7 @@ async fn main() {
                     println!("Loop reset.. ");
 
                     // Restore the recording memory
-                    unsafe {
-                        std::ptr::copy_nonoverlapping(
-                            loop_data.memory.as_ptr(),
-                            MEMORY_BASE_ADDR as *mut u8,
-                            MEMORY_LENGTH,
-                        );
-                    }
+                    memory.restore(&loop_data.memory);
 
                     // Reset the game state
                     game = loop_data.game_state.clone();
@@ -172,11 +189,45 @@ async fn main() {
             memory: &mut memory,
             buttons: &buttons,
             frame_time,
+            show_ease_visualizer,
         };
 
         // Call the game function
         game_update_and_render(&context, &mut game, &macroquad);
 
+        // Keep the memory header current in case the memory is backed by a file
+        memory.sync_header();
+
+        // Report any writes outside of an allocation made by this frame
+        for corruption in memory.check_canaries() {
+            eprintln!("ERROR: Game memory corruption: {corruption}");
+        }
+
+        // Report running low on memory once, well before running out
+        if let Some(warning) = memory.take_usage_warning() {
+            eprintln!("WARNING: {warning}");
+        }
+
+        // Draw the memory usage on top of the game
+        if show_memory_overlay {
+            memory.draw_overlay(&macroquad);
+        }
+
+        // The state is only diffed from the first frame it exists in
+        if snapshot_state.is_none() {
+            snapshot_state = game.clone();
+        }
+
+        // Draw the changes since the last snapshot on top of the game
+        if show_diff_overlay {
+            let mut diff = memory.diff(&snapshot, &memory.data_as_vec(), &diff_registry);
+            if let (Some(before), Some(after)) = (&snapshot_state, &game) {
+                diff.diff_value("state", before, after);
+            }
+
+            diff.draw_overlay(&macroquad);
+        }
+
         // Goto next frame
         next_frame().await
     }